@@ -1,4 +1,25 @@
 // Command constant
+pub const CMD_CONFIG: &'static str = "config";
+pub const CMD_CONFIG_MIGRATE: &'static str = "migrate";
+pub const CMD_CONFIG_PATHS: &'static str = "paths";
+pub const CMD_FILTERS: &'static str = "filters";
+pub const ARG_CONFIG: &'static str = "config";
 pub const ARG_DRY: &'static str = "dry";
 pub const ARG_FILTER: &'static str = "filter";
+pub const ARG_IMAGE: &'static str = "image";
+pub const ARG_IMAGE_PICK: &'static str = "image-pick";
+pub const ARG_LOCK_BACKEND: &'static str = "lock.backend";
+pub const ARG_LOCK_COLOR: &'static str = "lock.color";
+pub const ARG_LOCK_IGNORE_EMPTY_PASSWORD: &'static str = "lock.ignore-empty-password";
+pub const ARG_LOCK_INDICATOR: &'static str = "lock.indicator";
+pub const ARG_LOCK_RAW: &'static str = "lock.raw";
+pub const ARG_MONITOR: &'static str = "monitor";
+pub const ARG_NO_CONFIG: &'static str = "no-config";
+pub const ARG_OUTPUT: &'static str = "output";
 pub const ARG_PARAM: &'static str = "parameter";
+pub const ARG_PRESET: &'static str = "preset";
+pub const ARG_PRESETS: &'static str = "presets";
+pub const ARG_RAW: &'static str = "raw";
+pub const ARG_SCREENSHOT_BACKEND: &'static str = "screenshot.backend";
+pub const ARG_SCRIPT: &'static str = "script";
+pub const ARG_SPLIT_MONITORS: &'static str = "split-monitors";
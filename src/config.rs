@@ -8,11 +8,18 @@ use std::io::Read;
 use std::path::Path;
 
 use self::clap::ArgMatches;
-use self::yaml_rust::{Yaml, YamlLoader};
+use self::yaml_rust::Yaml;
 
+use app;
 use cmd;
+use config_migrate;
+use config_schema;
 use err::{Error, Result};
 use yaml_helper::YamlHelper;
+use yaml_marked::Marked;
+
+/// Suffix for configuration keys, to append to a list instead of replacing it.
+pub const APPEND_SUFFIX: &'static str = "+";
 
 /// App configuration structure.
 pub struct Config {
@@ -42,41 +49,98 @@ impl Config {
 
     /// Merge the current configuration with the `other` given Yaml.
     ///
-    /// `other` overrides the current configuration.
+    /// `other` overrides the current configuration, see `Config::merge_yaml` for how values are
+    /// merged.
+    ///
+    /// Presets are kept as is, and replace presets with the same name,
+    /// because they are merged when they are applied.
     fn merge(&mut self, other: Yaml) -> Result<()> {
-        // Set the data if it's none
-        if self.data.is_none() {
-            self.data = Some(other);
-            return Ok(());
+        let mut other = match other {
+            Yaml::Hash(other) => other,
+            Yaml::Null => return Ok(()),
+            _ => return Err(Error::config("The configuration must be a map")),
+        };
+        let presets = other.remove(&Yaml::String(cmd::ARG_PRESETS.into()));
+
+        // Initialize the configuration, and merge into it
+        let data = self.data.get_or_insert_with(|| Yaml::Hash(BTreeMap::new()));
+        Config::merge_yaml(data, Yaml::Hash(other));
+
+        // Add the presets, replacing presets with the same name
+        match presets {
+            Some(Yaml::Hash(presets)) => if let Yaml::Hash(ref mut root) = *data {
+                let all = root.entry(Yaml::String(cmd::ARG_PRESETS.into()))
+                    .or_insert_with(|| Yaml::Hash(BTreeMap::new()));
+                if all.as_hash().is_none() {
+                    *all = Yaml::Hash(BTreeMap::new());
+                }
+                if let Yaml::Hash(ref mut all) = *all {
+                    all.extend(presets);
+                }
+            },
+            Some(Yaml::Null) | None => {},
+            Some(_) => return Err(Error::config("The presets must be a map")),
         }
 
-        // Get the other hash
-        match other {
-            Yaml::Hash(other_root) =>
-                // Get the mutable data hash
-                // TODO: Don't unwrap, unsafe
-                match *self.data.as_mut().unwrap() {
-                    Yaml::Hash(ref mut root) => {
-                        // Merge the hashes
-                        // TODO: Refactor this into a functional for_each
-                        for (key, value) in other_root.into_iter() {
-                            root.insert(key, value);
-                        }
-
-                        Ok(())
-                    },
-
-                    _ => Err(Error::new("Failed to access configuration root")),
-                },
-
-            _ => Err(Error::new("Failed to access other configuration root"))
+        Ok(())
+    }
+
+    /// Merge the `other` Yaml value into `base` recursively.
+    ///
+    /// Maps are merged key by key, so a single key can be overridden.
+    /// Other values, including lists, replace the `base` value.
+    /// A key suffixed with `+`, such as `filter+`, appends to the list in `base` instead.
+    /// Null values, such as a key without a value, don't override anything.
+    fn merge_yaml(base: &mut Yaml, other: Yaml) {
+        let other = match other {
+            Yaml::Hash(other) => other,
+            Yaml::Null => return,
+            other => {
+                *base = other;
+                return;
+            },
+        };
+
+        // Maps are merged into a map
+        if base.as_hash().is_none() {
+            *base = Yaml::Hash(BTreeMap::new());
+        }
+        if let Yaml::Hash(ref mut base) = *base {
+            for (key, value) in other {
+                // Append to the list for keys with the append suffix
+                let append = key.as_str()
+                    .filter(|key| key.len() > APPEND_SUFFIX.len() && key.ends_with(APPEND_SUFFIX))
+                    .map(|key| Yaml::String(key[..key.len() - APPEND_SUFFIX.len()].into()));
+                if let Some(key) = append {
+                    let items = match value {
+                        Yaml::Array(items) => items,
+                        Yaml::Null => vec![],
+                        item => vec![item],
+                    };
+                    if let Some(&mut Yaml::Array(ref mut list)) = base.get_mut(&key) {
+                        list.extend(items);
+                        continue;
+                    }
+                    base.insert(key, Yaml::Array(items));
+                    continue;
+                }
+
+                // Merge into the existing value
+                Config::merge_yaml(base.entry(key).or_insert(Yaml::Null), value);
+            }
         }
     }
 
     /// Merge the file at the given `path` into the configuration.
     ///
-    /// Any load or parse errors are returned if merging failed.
-    pub fn merge_file<'a>(&'a mut self, path: &Path) -> Result<'a, ()> {
+    /// Any load or parse errors are returned if merging failed, which include the file path.
+    pub fn merge_file(&mut self, path: &Path) -> Result<()> {
+        self.merge_file_inner(path)
+            .map_err(|err| Error::config(format!("Failed to load configuration file {:?}", path)).caused_by(err))
+    }
+
+    /// Merge the file at the given `path` into the configuration, see `merge_file`.
+    fn merge_file_inner(&mut self, path: &Path) -> Result<()> {
         // Open the file
         let mut file = File::open(path)?;
 
@@ -84,14 +148,51 @@ impl Config {
         let mut source = String::new();
         file.read_to_string(&mut source)?;
 
-        // Load the first YAML document, and merge it
-        match YamlLoader::load_from_str(&source)?.into_iter().next() {
-            Some(doc) => {
-                self.merge(doc)?;
-                Ok(())
-            },
-            None => Ok(()),
+        // Load the first YAML document
+        let mut doc = match Marked::load(&source)? {
+            Some(doc) => doc,
+            None => return Ok(()),
+        };
+
+        // Warn about configurations for a newer version
+        if let Some(version) = config_migrate::version(&doc).filter(|version| config_migrate::is_newer(version)) {
+            eprintln!(
+                "{} warning: configuration file {:?} is for {} {}, which is newer than this version {}, \
+                some settings may not be supported",
+                app::NAME,
+                path,
+                app::NAME,
+                version,
+                app::VERSION,
+            );
         }
+
+        // Migrate deprecated settings
+        let migrations = config_migrate::migrate(&mut doc);
+        if !migrations.is_empty() {
+            eprintln!(
+                "{} warning: configuration file {:?} uses deprecated settings, \
+                run '{} {} {}' to upgrade it:\n  - {}",
+                app::NAME,
+                path,
+                app::NAME,
+                cmd::CMD_CONFIG,
+                cmd::CMD_CONFIG_MIGRATE,
+                migrations.join("\n  - "),
+            );
+        }
+
+        // Validate the configuration, leave out invalid settings with a warning, and merge it
+        let (yaml, problems) = config_schema::validate(&doc, false);
+        if !problems.is_empty() {
+            eprintln!(
+                "{} warning: configuration file {:?} has invalid settings, ignoring them:\n  - {}",
+                app::NAME,
+                path,
+                problems.join("\n  - "),
+            );
+        }
+        self.merge(yaml)
     }
 
     /// Get the Yaml property at the given `node`.
@@ -151,40 +252,42 @@ impl Config {
         self.set(node, Yaml::Array(list))
     }
 
-    /// Get a key value dictionary as `(String, String)` in a `HashMap` at the given `node`.
+    /// Get a key value dictionary as `(String, String)` in a `BTreeMap` at the given `node`.
+    ///
+    /// Scalar values are converted into strings, and an empty value is an empty string.
     ///
     /// The `def` value is returned if the given property was not found.
     ///
-    /// Errors are returned if parsing the dictionary resulted in a problem.
-    pub fn get_dict<'a>(&self, node: &str, def: BTreeMap<String, String>) -> Result<'a, BTreeMap<String, String>> {
-        // The data must be available
-        match self.data.as_ref() {
-            Some(data) =>
-                // The given node must be available
-                match data.property(node) {
-                    Some(object) => {
-                        // Parse the object as dictionary
-                        match object.as_hash() {
-                            Some(map) => {
-                                // Map the Yaml objects into string results
-                                // TODO: Somehow collect errors here
-                                Ok(
-                                    map.into_iter()
-                                        .map(|(key, val)| (
-                                            // Map the Yaml node and value into owned strings
-                                            key.as_str().unwrap().into(),
-                                            val.as_str().unwrap().into(),
-                                        ))
-                                        .collect()
-                                )
-                            },
-                            None =>
-                                Err(Error::new("The property is not in Hash format, unable to parse it as dictionary"))
-                        }
-                    },
-                    None => Ok(def),
-                },
-            None => Ok(def),
+    /// Errors are returned if the property isn't a map, or if it contains a list or map.
+    pub fn get_dict(&self, node: &str, def: BTreeMap<String, String>) -> Result<BTreeMap<String, String>> {
+        let map = match self.get(node) {
+            Some(Yaml::Hash(map)) => map,
+            Some(Yaml::Null) | None => return Ok(def),
+            Some(_) => return Err(Error::config(format!("The '{}' property must be a map", node))),
+        };
+
+        // Map the Yaml keys and values into owned strings
+        map.iter()
+            .map(|(key, val)| match (Config::to_string(key), Config::to_string(val)) {
+                (Some(key), Some(val)) => Ok((key, val)),
+                (Some(key), None) => Err(Error::config(
+                    format!("The value of '{}' in '{}' must be a single value", key, node)
+                )),
+                (None, _) => Err(Error::config(format!("The keys in '{}' must be single values", node))),
+            })
+            .collect()
+    }
+
+    /// Convert the given scalar Yaml `value` into a string.
+    ///
+    /// `None` is returned for lists and maps.
+    fn to_string(value: &Yaml) -> Option<String> {
+        match *value {
+            Yaml::String(ref value) | Yaml::Real(ref value) => Some(value.clone()),
+            Yaml::Integer(value) => Some(value.to_string()),
+            Yaml::Boolean(value) => Some(value.to_string()),
+            Yaml::Null => Some(String::new()),
+            _ => None,
         }
     }
 
@@ -194,20 +297,80 @@ impl Config {
             node,
             Yaml::Hash(
                 dict.into_iter()
-                    .map(|(ref key, ref val)| (
-                        Yaml::from_str(key),
-                        Yaml::from_str(val),
+                    .map(|(key, val)| (
+                        Yaml::String(key),
+                        Yaml::String(val),
                     ))
                     .collect()
             )
         )
     }
 
+    /// Apply the preset with the given `name` from the `presets` map.
+    ///
+    /// The preset is merged over the current configuration like another dotfile,
+    /// see `Config::merge_yaml`. Maps such as `parameter` are merged key by key,
+    /// other values such as the `filter` list replace the configured ones,
+    /// and keys such as `filter+` append to them.
+    ///
+    /// An error is returned if the preset doesn't exist.
+    pub fn apply_preset(&mut self, name: &str) -> Result<()> {
+        // Find the preset by it's name
+        let presets = self.get(cmd::ARG_PRESETS).and_then(|presets| presets.as_hash());
+        let preset = presets.and_then(|presets| presets.get(&Yaml::String(name.into())));
+        let mut preset = match preset {
+            Some(Yaml::Hash(preset)) => preset.clone(),
+            Some(_) => return Err(Error::config(format!("Preset '{}' must be a map", name))),
+            None => {
+                let names: Vec<&str> = presets
+                    .map(|presets| presets.keys().filter_map(|name| name.as_str()).collect())
+                    .unwrap_or_default();
+                return Err(Error::config(match names.len() {
+                    0 => format!("Unknown preset '{}', no presets are configured", name),
+                    _ => format!("Unknown preset '{}', available presets: {}", name, names.join(", ")),
+                }));
+            },
+        };
+
+        // Presets can't select other presets
+        preset.remove(&Yaml::String(cmd::ARG_PRESET.into()));
+        preset.remove(&Yaml::String(cmd::ARG_PRESETS.into()));
+
+        self.merge(Yaml::Hash(preset))
+    }
+
     /// Parse a set of command line argument matches.
     pub fn parse_matches(&mut self, matches: &ArgMatches) -> Result<()> {
-        // TODO: Don't unwrap, but try! the result!
-        self.parse_i3_params(matches).unwrap();
-        self.parse_filters(matches).unwrap();
+        // Apply the selected preset first, so other arguments are applied on top of it
+        let preset = matches.value_of(cmd::ARG_PRESET)
+            .map(|preset| preset.to_owned())
+            .or_else(|| self.get(cmd::ARG_PRESET).and_then(|preset| preset.as_str()).map(|preset| preset.to_owned()));
+        if let Some(preset) = preset {
+            self.apply_preset(&preset)?;
+        }
+
+        self.parse_i3_params(matches)?;
+        self.parse_filters(matches)?;
+
+        // Image to use instead of a screenshot
+        if let Some(image) = matches.value_of(cmd::ARG_IMAGE) {
+            self.set(cmd::ARG_IMAGE, Yaml::String(image.into()))?;
+        }
+
+        // Output path to render the image to
+        if let Some(output) = matches.value_of(cmd::ARG_OUTPUT) {
+            self.set(cmd::ARG_OUTPUT, Yaml::String(output.into()))?;
+        }
+
+        // Path to export a lock script to
+        if let Some(script) = matches.value_of(cmd::ARG_SCRIPT) {
+            self.set(cmd::ARG_SCRIPT, Yaml::String(script.into()))?;
+        }
+
+        // Pipe the image to the lock screen
+        if matches.is_present(cmd::ARG_RAW) {
+            self.set(cmd::ARG_LOCK_RAW, Yaml::Boolean(true))?;
+        }
 
         // Dry run
         if matches.is_present(cmd::ARG_DRY) {
@@ -284,3 +447,110 @@ impl Config {
         self.set_list(cmd::ARG_FILTER, cfg_filters)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::clap::App;
+    use super::yaml_rust::{Yaml, YamlLoader};
+
+    use super::Config;
+
+    /// Load the given YAML `source`.
+    fn yaml(source: &str) -> Yaml {
+        YamlLoader::load_from_str(source).unwrap().remove(0)
+    }
+
+    /// Merge the YAML `other` into `base`, and get the result.
+    fn merge(base: &str, other: &str) -> Yaml {
+        let mut base = yaml(base);
+        Config::merge_yaml(&mut base, yaml(other));
+        base
+    }
+
+    /// Build a configuration from the given YAML `source`.
+    fn config(source: &str) -> Config {
+        let mut config = Config::default();
+        config.merge(yaml(source)).unwrap();
+        config
+    }
+
+    /// Presets used by the preset tests.
+    const PRESETS: &'static str = "filter: [blur]\nparameter: {radius: 90, clock: true}\npresets:\n  \
+        pixel: {filter: [pixelate], parameter: {radius: 120}}\n  \
+        extra: {filter+: [invert], parameter: {clock: false}}";
+
+    #[test]
+    fn merge_recursive() {
+        assert_eq!(
+            merge(
+                "dry: false\nparameter: {radius: 90, clock: true}\nmonitor: {eDP-1: {filter: [blur]}}",
+                "dry: true\nparameter: {radius: 120}\nmonitor: {HDMI-1: {filter: [invert]}}",
+            ),
+            yaml("dry: true\nparameter: {radius: 120, clock: true}\nmonitor: {eDP-1: {filter: [blur]}, HDMI-1: {filter: [invert]}}"),
+        );
+    }
+
+    #[test]
+    fn merge_replaces() {
+        assert_eq!(merge("filter: [blur, invert]", "filter: [greyscale]"), yaml("filter: [greyscale]"));
+        assert_eq!(merge("filter: [blur]", "filter: greyscale"), yaml("filter: greyscale"));
+        assert_eq!(merge("parameter: {radius: 90}", "parameter: plain"), yaml("parameter: plain"));
+        assert_eq!(merge("image: a.png", "parameter: {radius: 90}"), yaml("image: a.png\nparameter: {radius: 90}"));
+    }
+
+    #[test]
+    fn merge_append() {
+        assert_eq!(merge("filter: [blur]", "filter+: [invert, greyscale]"), yaml("filter: [blur, invert, greyscale]"));
+        assert_eq!(merge("filter: [blur]", "filter+: invert"), yaml("filter: [blur, invert]"));
+        assert_eq!(merge("dry: true", "filter+: invert"), yaml("dry: true\nfilter: [invert]"));
+        assert_eq!(merge("filter: blur", "filter+: invert"), yaml("filter: [invert]"));
+        assert_eq!(
+            merge("monitor: {eDP-1: {filter: [blur]}}", "monitor: {eDP-1: {filter+: [invert]}}"),
+            yaml("monitor: {eDP-1: {filter: [blur, invert]}}"),
+        );
+        assert_eq!(merge("filter: [blur]", "filter+:"), yaml("filter: [blur]"));
+    }
+
+    #[test]
+    fn merge_null() {
+        // Empty keys don't override the lower layer
+        assert_eq!(merge("parameter: {radius: 90}", "parameter:"), yaml("parameter: {radius: 90}"));
+        assert_eq!(merge("image: a.png", "image: ~"), yaml("image: a.png"));
+        assert_eq!(merge("dry: true", "~"), yaml("dry: true"));
+        assert_eq!(merge("parameter: {radius: 90}", "parameter: {radius: ~, clock: true}"), yaml("parameter: {radius: 90, clock: true}"));
+        assert_eq!(merge("dry: true", "image:"), yaml("dry: true\nimage: ~"));
+    }
+
+    #[test]
+    fn preset_apply() {
+        let mut config = config(PRESETS);
+        config.apply_preset("pixel").unwrap();
+        assert_eq!(config.get("filter"), Some(&yaml("[pixelate]")));
+        assert_eq!(config.get("parameter"), Some(&yaml("{radius: 120, clock: true}")));
+    }
+
+    #[test]
+    fn preset_append() {
+        let mut config = config(PRESETS);
+        config.apply_preset("extra").unwrap();
+        assert_eq!(config.get("filter"), Some(&yaml("[blur, invert]")));
+        assert_eq!(config.get("parameter"), Some(&yaml("{radius: 90, clock: false}")));
+    }
+
+    #[test]
+    fn preset_active() {
+        // The preset selected in the configuration is applied when parsing the arguments
+        let mut config = config(&format!("{}\npreset: extra", PRESETS));
+        config.parse_matches(&App::new("test").get_matches_from(vec!["test"])).unwrap();
+        assert_eq!(config.get("filter"), Some(&yaml("[blur, invert]")));
+    }
+
+    #[test]
+    fn preset_unknown() {
+        let err = config(PRESETS).apply_preset("blur").unwrap_err().to_string();
+        assert!(err.contains("Unknown preset 'blur', available presets: extra, pixel"), "{}", err);
+
+        let err = config("filter: [blur]").apply_preset("blur").unwrap_err().to_string();
+        assert!(err.contains("Unknown preset 'blur', no presets are configured"), "{}", err);
+    }
+}
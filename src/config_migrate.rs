@@ -0,0 +1,423 @@
+extern crate version_compare;
+extern crate yaml_rust;
+
+use self::version_compare::{CompOp, VersionCompare};
+use self::yaml_rust::{Yaml, YamlEmitter};
+
+use app;
+use cmd;
+use config::APPEND_SUFFIX;
+use config_schema;
+use err::{Error, Result};
+use lock::Opt;
+use yaml_marked::{Marked, Node};
+use yaml_helper::YamlHelper;
+
+/// Configuration key holding the version of i3lock-slick a configuration targets.
+pub const KEY_VERSION: &'static str = "version";
+
+/// A migration, rewriting deprecated settings into their current form.
+struct Migration {
+    /// What the migration does, as shown to the user.
+    description: &'static str,
+
+    /// The version that deprecated the settings, configurations for it or later are not migrated.
+    deprecated: &'static str,
+
+    /// Migrate the given settings, being the configuration root or a preset.
+    ///
+    /// Returns whether anything was changed.
+    apply: fn(&mut Vec<(Marked, Marked)>) -> bool,
+}
+
+/// All migrations, in the order they are applied.
+static MIGRATIONS: &'static [Migration] = &[
+    Migration {
+        description: "Move the color, ignore-empty-password and no-unlock-indicator parameters \
+            to the 'lock' settings",
+        deprecated: "0.0.2",
+        apply: migrate_lock_params,
+    },
+    Migration {
+        description: "Fix the misspelled 'trinagle' resize filter",
+        deprecated: "0.0.2",
+        apply: |settings| rewrite_filters(settings, migrate_resize_triangle),
+    },
+];
+
+/// An upgraded configuration, see `upgrade`.
+pub struct Upgrade {
+    /// The upgraded configuration.
+    pub source: String,
+
+    /// The descriptions of the applied migrations.
+    pub migrations: Vec<&'static str>,
+
+    /// Problems in the configuration, invalid values are left out of the upgraded configuration.
+    pub problems: Vec<String>,
+}
+
+/// Migrate the deprecated settings in the given configuration `doc`, and in it's presets.
+///
+/// Settings are only migrated if the configuration has no version,
+/// or a version from before they were deprecated.
+/// The descriptions of the applied migrations are returned.
+pub fn migrate(doc: &mut Marked) -> Vec<&'static str> {
+    let version = version(doc).map(|version| version.trim().to_owned());
+    let root = match doc.node {
+        Node::Map(ref mut root) => root,
+        _ => return vec![],
+    };
+
+    MIGRATIONS.iter()
+        .filter(|migration| match version {
+            Some(ref version) => VersionCompare::compare_to(version, migration.deprecated, &CompOp::Lt)
+                .unwrap_or(true),
+            None => true,
+        })
+        .filter(|migration| {
+            let mut changed = (migration.apply)(root);
+            if let Some(Node::Map(presets)) = find_mut(root, cmd::ARG_PRESETS).map(|presets| &mut presets.node) {
+                for (_, preset) in presets.iter_mut() {
+                    if let Node::Map(ref mut preset) = preset.node {
+                        changed |= (migration.apply)(preset);
+                    }
+                }
+            }
+            changed
+        })
+        .map(|migration| migration.description)
+        .collect()
+}
+
+/// Get the version of i3lock-slick the given configuration `doc` targets, if set.
+pub fn version(doc: &Marked) -> Option<&str> {
+    match doc.node {
+        Node::Map(ref root) => root.iter()
+            .find(|(key, _)| key.as_scalar() == Some(KEY_VERSION))
+            .and_then(|(_, version)| version.as_scalar()),
+        _ => None,
+    }
+}
+
+/// Check whether the given configuration `version` is newer than this application.
+///
+/// Versions that can't be compared are never newer.
+pub fn is_newer(version: &str) -> bool {
+    VersionCompare::compare_to(version.trim(), app::VERSION, &CompOp::Gt).unwrap_or(false)
+}
+
+/// Upgrade the configuration in the given `source` to the current version.
+///
+/// Deprecated settings are migrated, and the version is set to the version of this application.
+/// Unknown keys are kept, invalid values are left out and reported as problems.
+/// `None` is returned if the configuration is already up to date. Comments are not preserved.
+///
+/// An error is returned if the configuration targets a newer version, or if it can't be parsed.
+pub fn upgrade(source: &str) -> Result<Option<Upgrade>> {
+    let mut doc = match Marked::load(source)? {
+        Some(doc) => doc,
+        None => return Ok(None),
+    };
+
+    // Don't touch configurations for newer versions
+    let version = version(&doc).map(|version| version.to_owned());
+    if let Some(ref version) = version {
+        if is_newer(version) {
+            return Err(Error::config(format!(
+                "The configuration is for {} {}, which is newer than this version {}",
+                app::NAME,
+                version,
+                app::VERSION,
+            )));
+        }
+    }
+
+    // Migrate the settings, skip if nothing changed
+    let migrations = migrate(&mut doc);
+    let outdated = version.map(|version| version.trim() != app::VERSION).unwrap_or(false);
+    if migrations.is_empty() && !outdated {
+        return Ok(None);
+    }
+
+    // Validate the result, and emit it with the current version
+    let (mut yaml, problems) = config_schema::validate(&doc, true);
+    yaml.set_property(KEY_VERSION, Yaml::String(app::VERSION.into()))?;
+    let mut source = String::new();
+    YamlEmitter::new(&mut source).dump(&yaml)
+        .map_err(|err| Error::config(format!("Failed to emit the configuration: {:?}", err)))?;
+    source.push('\n');
+
+    Ok(Some(Upgrade { source, migrations, problems }))
+}
+
+/// Find the value of the key with the given `name` in the map `entries`.
+fn find_mut<'a>(entries: &'a mut [(Marked, Marked)], name: &str) -> Option<&'a mut Marked> {
+    entries.iter_mut()
+        .find(|(key, _)| key.as_scalar() == Some(name))
+        .map(|(_, value)| value)
+}
+
+/// Set the key with the given `name` in the map `entries` to `value`, replacing an existing value.
+///
+/// The key is marked at the position of the value, as it's moved from there.
+fn set(entries: &mut Vec<(Marked, Marked)>, name: &str, value: Marked) {
+    if let Some(existing) = find_mut(entries, name) {
+        *existing = value;
+        return;
+    }
+    let key = Marked { node: Node::Scalar(name.into(), true), mark: value.mark };
+    entries.push((key, value));
+}
+
+/// Move the i3lock `color`, `ignore-empty-password` and `no-unlock-indicator` parameters
+/// to the common lock screen settings, which work with every lock screen backend.
+fn migrate_lock_params(settings: &mut Vec<(Marked, Marked)>) -> bool {
+    let moved: Vec<(&'static str, Marked)> = match find_mut(settings, cmd::ARG_PARAM) {
+        Some(&mut Marked { node: Node::Map(ref mut params), .. }) => {
+            let mut moved = vec![];
+            params.retain(|(key, value)| {
+                let opt = match key.as_scalar().and_then(Opt::find) {
+                    Some(opt) => opt,
+                    None => return true,
+                };
+
+                // Flags are enabled without a value, or with a truthy one
+                let enabled = value.is_null() || match value.as_scalar().map(|value| value.trim().to_lowercase()) {
+                    Some(value) => value.is_empty() || value == "true" || value == "yes" || value == "on",
+                    None => false,
+                };
+                let flag = |enabled: bool| Marked { node: Node::Scalar(enabled.to_string(), true), mark: value.mark };
+                match opt.name {
+                    "color" => moved.push(("color", value.clone())),
+                    "ignore-empty-password" => moved.push(("ignore-empty-password", flag(enabled))),
+                    "no-unlock-indicator" if enabled => moved.push(("indicator", flag(false))),
+                    "no-unlock-indicator" => {},
+                    _ => return true,
+                }
+                false
+            });
+            moved
+        },
+        _ => return false,
+    };
+    if moved.is_empty() {
+        return false;
+    }
+
+    // Remove the parameters if none are left
+    settings.retain(|(key, value)| match value.node {
+        Node::Map(ref params) => !params.is_empty() || key.as_scalar() != Some(cmd::ARG_PARAM),
+        _ => true,
+    });
+
+    // Add the settings to the lock map
+    let mark = moved[0].1.mark;
+    if find_mut(settings, "lock").is_none() {
+        set(settings, "lock", Marked { node: Node::Map(vec![]), mark });
+    }
+    let lock = find_mut(settings, "lock").unwrap();
+    if let Node::Map(ref mut lock) = lock.node {
+        for (name, value) in moved {
+            set(lock, name, value);
+        }
+    } else {
+        lock.node = Node::Map(moved.into_iter()
+            .map(|(name, value)| (Marked { node: Node::Scalar(name.into(), true), mark: value.mark }, value))
+            .collect());
+    }
+
+    true
+}
+
+/// Fix the `trinagle` resize filter in the given `filter`, which was misspelled in the documentation.
+///
+/// The fixed filter is returned, or `None` if it doesn't need to be fixed.
+fn migrate_resize_triangle(filter: &str) -> Option<String> {
+    let mut parts = filter.splitn(2, ':');
+    let name = parts.next().unwrap_or("").trim();
+    let props = match parts.next() {
+        Some(props) if name == "resize" => props,
+        _ => return None,
+    };
+
+    let mut changed = false;
+    let props: Vec<&str> = props.split(';')
+        .map(|prop| {
+            let mut parts = prop.splitn(2, '=');
+            match (parts.next().map(|name| name.trim()), parts.next().map(|value| value.trim())) {
+                (Some("filter"), Some("trinagle")) => {
+                    changed = true;
+                    "filter=triangle"
+                },
+                _ => prop,
+            }
+        })
+        .collect();
+
+    if changed { Some(format!("{}:{}", name, props.join(";"))) } else { None }
+}
+
+/// Rewrite the filters in the given `settings` with `rewrite`, including the per monitor filters.
+///
+/// `rewrite` returns the new filter definition, or `None` to keep the filter as is.
+/// Returns whether any filter was rewritten.
+fn rewrite_filters<F>(settings: &mut [(Marked, Marked)], rewrite: F) -> bool
+    where F: Fn(&str) -> Option<String>
+{
+    /// Rewrite the filter lists in the map `entries`.
+    fn rewrite_lists<F: Fn(&str) -> Option<String>>(entries: &mut [(Marked, Marked)], rewrite: &F) -> bool {
+        let mut changed = false;
+        for (key, value) in entries.iter_mut() {
+            let name = key.as_scalar().unwrap_or("");
+            if name.trim_end_matches(APPEND_SUFFIX) != cmd::ARG_FILTER {
+                continue;
+            }
+
+            // Filters may be a list, or a single filter
+            let filters = match value.node {
+                Node::Seq(ref mut filters) => filters.iter_mut().collect(),
+                Node::Scalar(..) => vec![value],
+                _ => vec![],
+            };
+            for filter in filters {
+                let migrated = match filter.node {
+                    Node::Scalar(ref filter, _) => rewrite(filter),
+                    _ => None,
+                };
+                if let Some(migrated) = migrated {
+                    filter.node = Node::Scalar(migrated, false);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    let mut changed = rewrite_lists(settings, &rewrite);
+    if let Some(Node::Map(monitors)) = find_mut(settings, cmd::ARG_MONITOR).map(|monitors| &mut monitors.node) {
+        for (_, monitor) in monitors.iter_mut() {
+            if let Node::Map(ref mut monitor) = monitor.node {
+                changed |= rewrite_lists(monitor, &rewrite);
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::yaml_rust::{Yaml, YamlLoader};
+
+    use app;
+    use yaml_marked::Marked;
+
+    use super::{migrate, upgrade, Upgrade};
+
+    /// Migrate the given configuration `source`, and get the descriptions of the applied migrations.
+    fn migrations(source: &str) -> Vec<&'static str> {
+        migrate(&mut Marked::load(source).unwrap().unwrap())
+    }
+
+    /// Upgrade the given configuration `source`, which must not be up to date.
+    fn upgrade_changed(source: &str) -> Upgrade {
+        upgrade(source).unwrap().expect("configuration is up to date")
+    }
+
+    /// Load the given YAML `source`.
+    fn yaml(source: &str) -> Yaml {
+        YamlLoader::load_from_str(source).unwrap().remove(0)
+    }
+
+    /// Load the given YAML `source`, with the version of this application added.
+    fn versioned(source: &str) -> Yaml {
+        yaml(&format!("{}\nversion: '{}'", source, app::VERSION))
+    }
+
+    #[test]
+    fn resize_triangle() {
+        let upgraded = upgrade_changed("filter:\n  - 'resize:width=50%;height=50%;filter=trinagle'\n  - 'blur'\n");
+        assert_eq!(upgraded.migrations, vec!["Fix the misspelled 'trinagle' resize filter"]);
+        assert_eq!(yaml(&upgraded.source), versioned("filter: ['resize:width=50%;height=50%;filter=triangle', blur]"));
+
+        // Per monitor filters, appended filters and presets are fixed as well
+        let upgraded = upgrade_changed("\
+filter+: 'resize:width=10;height=10;filter=trinagle'
+monitor:
+  eDP-1:
+    filter: ['resize:width=10;height=10;filter = trinagle']
+presets:
+  small:
+    filter: 'resize:width=10;height=10;filter=trinagle'
+");
+        assert!(!upgraded.source.contains("trinagle"), "{}", upgraded.source);
+        assert_eq!(upgraded.source.matches("filter=triangle").count(), 3, "{}", upgraded.source);
+
+        // Other filters are left alone
+        assert!(migrations("filter: ['text:text=trinagle', 'resize:filter=triangle']").is_empty());
+    }
+
+    #[test]
+    fn lock_params() {
+        let upgraded = upgrade_changed("parameter:\n  color: 'ff0000'\n  e:\n  u: true\n  radius: 90\n");
+        assert_eq!(upgraded.migrations.len(), 1);
+        assert!(upgraded.problems.is_empty(), "{:?}", upgraded.problems);
+        assert_eq!(yaml(&upgraded.source), versioned("\
+lock: {color: ff0000, ignore-empty-password: true, indicator: false}
+parameter: {radius: '90'}"));
+    }
+
+    #[test]
+    fn lock_params_merge() {
+        // Existing lock settings are kept, and the parameters are removed once empty
+        let upgraded = upgrade_changed("lock:\n  backend: i3lock\nparameter:\n  c: '000000'\n  no-unlock-indicator: false\n");
+        assert_eq!(yaml(&upgraded.source), versioned("lock: {backend: i3lock, color: '000000'}"));
+    }
+
+    #[test]
+    fn newer_version() {
+        assert!(upgrade("version: 999.0\nfilter: blur\n").is_err());
+    }
+
+    #[test]
+    fn deprecated_version() {
+        // Configurations for the version that deprecated the settings are not migrated
+        let source = format!("parameter: {{color: 'ff0000'}}\nversion: {}\n", app::VERSION);
+        assert!(migrations(&source).is_empty());
+        assert!(upgrade(&source).unwrap().is_none());
+
+        // Configurations for older versions are, such as those from the 0.0.1 template
+        assert_eq!(migrations("parameter: {color: 'ff0000'}\nversion: 0.0.0\n").len(), 1);
+        let upgraded = upgrade_changed("parameter: {color: 'ff0000'}\nversion: \"0.0.1\"\n");
+        assert_eq!(upgraded.migrations.len(), 1);
+        assert_eq!(yaml(&upgraded.source), versioned("lock: {color: ff0000}"));
+    }
+
+    #[test]
+    fn idempotent() {
+        let upgraded = upgrade_changed(
+            "parameter: {color: 'ff0000', clock: true}\nfilter: 'resize:width=10;height=10;filter=trinagle'\nunknown: 1\n",
+        );
+        assert_eq!(upgraded.migrations.len(), 2);
+        assert_eq!(upgraded.problems, vec!["Unknown key 'unknown' at line 3 column 1"]);
+        assert_eq!(yaml(&upgraded.source), versioned("\
+filter: ['resize:width=10;height=10;filter=triangle']
+lock: {color: ff0000}
+parameter: {clock: 'true'}
+unknown: 1"));
+
+        // Upgrading again doesn't change anything
+        assert!(upgrade(&upgraded.source).unwrap().is_none());
+        assert!(migrations(&upgraded.source).is_empty());
+
+        // Also without the version, for which all migrations are tried
+        let source = upgraded.source.replace(&format!("version: {}", app::VERSION), "");
+        assert!(migrations(&source).is_empty());
+    }
+
+    #[test]
+    fn up_to_date() {
+        assert!(upgrade("").unwrap().is_none());
+        assert!(upgrade("filter: blur\n").unwrap().is_none());
+    }
+}
@@ -0,0 +1,225 @@
+extern crate clap;
+extern crate shellexpand;
+
+use std::env;
+use std::path::PathBuf;
+
+use self::clap::ArgMatches;
+
+use app;
+use cmd;
+
+/// Environment variable holding configuration file paths to load, separated by `:`.
+pub const ENV_CONFIG: &'static str = "I3LOCK_SLICK_CONFIG";
+
+/// File name of the configuration file in XDG configuration directories.
+const CONFIG_FILE: &'static str = "config.yml";
+
+/// Path of the dotfile in the home directory.
+const HOME_DOTFILE: &'static str = "~/.i3lock-slick.yml";
+
+/// Where the configuration file paths came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    /// Given with `--config`.
+    Argument,
+
+    /// Given in the `I3LOCK_SLICK_CONFIG` environment variable.
+    Environment,
+
+    /// The default paths, found in the XDG configuration directories and the home directory.
+    Default,
+
+    /// Loading configuration files is disabled with `--no-config`.
+    Disabled,
+}
+
+/// The configuration files to load, in order.
+pub struct ConfigPaths {
+    /// The paths to load, later files override earlier ones.
+    pub paths: Vec<PathBuf>,
+
+    /// Where the paths came from.
+    pub source: Source,
+}
+
+impl ConfigPaths {
+    /// Find the configuration files to load, based on the given argument `matches`.
+    ///
+    /// Paths given with `--config` are used if any, then those in the `I3LOCK_SLICK_CONFIG`
+    /// environment variable, and the default paths otherwise.
+    pub fn find(matches: &ArgMatches) -> Self {
+        if matches.is_present(cmd::ARG_NO_CONFIG) {
+            return ConfigPaths { paths: vec![], source: Source::Disabled };
+        }
+
+        // Use explicitly given paths
+        if let Some(paths) = matches.values_of(cmd::ARG_CONFIG) {
+            return ConfigPaths {
+                paths: paths.map(expand).collect(),
+                source: Source::Argument,
+            };
+        }
+        if let Some(paths) = env::var_os(ENV_CONFIG).filter(|paths| !paths.is_empty()) {
+            return ConfigPaths {
+                paths: env::split_paths(&paths)
+                    .filter(|path| !path.as_os_str().is_empty())
+                    .map(|path| expand(&path.to_string_lossy()))
+                    .collect(),
+                source: Source::Environment,
+            };
+        }
+
+        ConfigPaths {
+            paths: ConfigPaths::defaults(),
+            source: Source::Default,
+        }
+    }
+
+    /// Get the default configuration file paths, in order.
+    ///
+    /// These are the configuration files in `$XDG_CONFIG_DIRS` with the least important first,
+    /// the one in `$XDG_CONFIG_HOME`, and the dotfile in the home directory.
+    pub fn defaults() -> Vec<PathBuf> {
+        let mut dirs = xdg_dirs("XDG_CONFIG_DIRS", "/etc/xdg");
+        dirs.reverse();
+        dirs.extend(xdg_dirs("XDG_CONFIG_HOME", "~/.config"));
+
+        let mut paths: Vec<PathBuf> = dirs.into_iter()
+            .map(|dir| dir.join(app::NAME).join(CONFIG_FILE))
+            .collect();
+        paths.push(expand(HOME_DOTFILE));
+        paths
+    }
+
+    /// Check whether the paths were given explicitly, in which case the files must exist.
+    pub fn is_explicit(&self) -> bool {
+        self.source == Source::Argument || self.source == Source::Environment
+    }
+}
+
+/// Get the directories from the XDG environment variable with the given `name`,
+/// separated by `:`, or the `default` if it isn't set.
+///
+/// Relative paths are ignored, as required by the XDG base directory specification.
+fn xdg_dirs(name: &str, default: &str) -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = env::var_os(name)
+        .map(|dirs| env::split_paths(&dirs).filter(|dir| dir.is_absolute()).collect())
+        .unwrap_or_default();
+
+    if dirs.is_empty() {
+        env::split_paths(default).map(|dir| expand(&dir.to_string_lossy())).collect()
+    } else {
+        dirs
+    }
+}
+
+/// Expand a leading `~` in the given `path` to the home directory.
+fn expand(path: &str) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(path).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+
+    use super::clap::{App, Arg, ArgMatches};
+    use tempdir::TempDir;
+
+    use app;
+    use cmd;
+
+    use super::{ConfigPaths, Source, ENV_CONFIG};
+
+    /// Parse the given command line `args`, for the configuration arguments only.
+    fn matches<'a>(args: &[&str]) -> ArgMatches<'a> {
+        App::new("test")
+            .arg(Arg::with_name(cmd::ARG_CONFIG)
+                .long(cmd::ARG_CONFIG)
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true))
+            .arg(Arg::with_name(cmd::ARG_NO_CONFIG)
+                .long(cmd::ARG_NO_CONFIG))
+            .get_matches_from(Some("test").iter().chain(args))
+    }
+
+    /// Check whether each of the given `paths` was found.
+    fn found(paths: &ConfigPaths) -> Vec<bool> {
+        paths.paths.iter().map(|path| path.is_file()).collect()
+    }
+
+    #[test]
+    fn argument() {
+        let dir = TempDir::new(app::NAME).unwrap();
+        let (first, second) = (dir.path().join("first.yml"), dir.path().join("second.yml"));
+        File::create(&second).unwrap();
+
+        let paths = ConfigPaths::find(&matches(&[
+            "--config", first.to_str().unwrap(),
+            "--config", second.to_str().unwrap(),
+        ]));
+        assert_eq!(paths.source, Source::Argument);
+        assert!(paths.is_explicit());
+        assert_eq!(paths.paths, vec![first, second]);
+        assert_eq!(found(&paths), vec![false, true]);
+    }
+
+    #[test]
+    fn disabled() {
+        let paths = ConfigPaths::find(&matches(&["--no-config"]));
+        assert_eq!(paths.source, Source::Disabled);
+        assert!(paths.paths.is_empty());
+    }
+
+    /// The environment is shared between tests, so all cases depending on it are in one test.
+    #[test]
+    fn search_order() {
+        let dir = TempDir::new(app::NAME).unwrap();
+        let path = |name: &str| dir.path().join(name);
+        File::create(path("env.yml")).unwrap();
+
+        // Paths given with --config take precedence over the environment
+        env::set_var(ENV_CONFIG, path("env.yml"));
+        let paths = ConfigPaths::find(&matches(&["--config", path("arg.yml").to_str().unwrap()]));
+        assert_eq!(paths.source, Source::Argument);
+        assert_eq!(paths.paths, vec![path("arg.yml")]);
+        assert_eq!(found(&paths), vec![false]);
+
+        // Paths in the environment are separated by colons, empty entries are skipped
+        env::set_var(ENV_CONFIG, format!("{}::{}", path("missing.yml").display(), path("env.yml").display()));
+        let paths = ConfigPaths::find(&matches(&[]));
+        assert_eq!(paths.source, Source::Environment);
+        assert!(paths.is_explicit());
+        assert_eq!(paths.paths, vec![path("missing.yml"), path("env.yml")]);
+        assert_eq!(found(&paths), vec![false, true]);
+
+        // The XDG directories are used otherwise, the least important first, relative ones are ignored
+        env::remove_var(ENV_CONFIG);
+        env::set_var("XDG_CONFIG_DIRS", format!("{}:relative:{}", path("high").display(), path("low").display()));
+        env::set_var("XDG_CONFIG_HOME", path("home"));
+        let config = |dir: &str| path(dir).join(app::NAME).join("config.yml");
+        fs::create_dir_all(config("low").parent().unwrap()).unwrap();
+        File::create(config("low")).unwrap();
+        let paths = ConfigPaths::find(&matches(&[]));
+        assert_eq!(paths.source, Source::Default);
+        assert!(!paths.is_explicit());
+        assert_eq!(paths.paths[..3].to_vec(), vec![config("low"), config("high"), config("home")]);
+        assert_eq!(found(&paths)[..3].to_vec(), vec![true, false, false]);
+        assert_eq!(paths.paths.len(), 4);
+        assert!(paths.paths[3].ends_with(".i3lock-slick.yml"));
+
+        // An empty environment variable is ignored, as are XDG variables without absolute paths
+        env::set_var(ENV_CONFIG, "");
+        env::set_var("XDG_CONFIG_DIRS", "relative");
+        env::remove_var("XDG_CONFIG_HOME");
+        let paths = ConfigPaths::find(&matches(&[]));
+        assert_eq!(paths.source, Source::Default);
+        assert_eq!(paths.paths[0], PathBuf::from("/etc/xdg").join(app::NAME).join("config.yml"));
+        assert!(paths.paths[1].ends_with(".config/i3lock-slick/config.yml"));
+        env::remove_var(ENV_CONFIG);
+        env::remove_var("XDG_CONFIG_DIRS");
+    }
+}
@@ -0,0 +1,288 @@
+extern crate yaml_rust;
+
+use std::collections::BTreeMap;
+
+use self::yaml_rust::Yaml;
+
+use cmd;
+use config::APPEND_SUFFIX;
+use config_migrate;
+use img::img_proc::ImgProcParser;
+use util;
+use yaml_marked::{Marked, Node};
+
+/// Maximum number of suggestions for an unknown key.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The kind of a configuration value.
+enum Kind {
+    /// A string, any scalar is accepted as it is written.
+    Str,
+
+    /// A boolean, also accepting `yes`, `no`, `on` and `off`.
+    Bool,
+
+    /// A filter definition, which must be valid.
+    Filter,
+
+    /// A list of values, a single value is accepted as list of one.
+    List(Box<Kind>),
+
+    /// A map with the given known keys.
+    Struct(Vec<(&'static str, Kind)>),
+
+    /// A map with any keys, and values of the given kind.
+    Map(Box<Kind>),
+}
+
+/// Build the schema of the configuration.
+///
+/// If `root` is false, the keys that are only used at the root are left out,
+/// as used for the presets themselves.
+fn schema(root: bool) -> Kind {
+    let filters = || Kind::List(Box::new(Kind::Filter));
+
+    let mut keys = vec![
+        (cmd::ARG_DRY, Kind::Bool),
+        (cmd::ARG_FILTER, filters()),
+        (cmd::ARG_IMAGE, Kind::Str),
+        (cmd::ARG_IMAGE_PICK, Kind::Str),
+        ("lock", Kind::Struct(vec![
+            ("backend", Kind::Str),
+            ("color", Kind::Str),
+            ("ignore-empty-password", Kind::Bool),
+            ("indicator", Kind::Bool),
+            ("raw", Kind::Bool),
+        ])),
+        (cmd::ARG_MONITOR, Kind::Map(Box::new(Kind::Struct(vec![
+            (cmd::ARG_FILTER, filters()),
+        ])))),
+        (cmd::ARG_OUTPUT, Kind::Str),
+        (cmd::ARG_PARAM, Kind::Map(Box::new(Kind::Str))),
+        ("screenshot", Kind::Struct(vec![
+            ("backend", Kind::Str),
+        ])),
+        (cmd::ARG_SCRIPT, Kind::Str),
+        (cmd::ARG_SPLIT_MONITORS, Kind::Bool),
+    ];
+    if root {
+        keys.push((cmd::ARG_PRESET, Kind::Str));
+        keys.push((cmd::ARG_PRESETS, Kind::Map(Box::new(schema(false)))));
+        keys.push((config_migrate::KEY_VERSION, Kind::Str));
+    }
+
+    Kind::Struct(keys)
+}
+
+/// Validate the given configuration `doc`, and convert it into Yaml.
+///
+/// Scalars are converted to the type of the key they are used for,
+/// so `color: 000000` is the string `000000` and `clock: true` passes `true` as parameter.
+///
+/// Unknown keys, values of the wrong type and invalid filters are left out, so the rest of the
+/// configuration can still be used. They are returned as problems, along with their line and column.
+/// If `keep_unknown` is true, unknown keys are kept as they are written instead.
+pub fn validate(doc: &Marked, keep_unknown: bool) -> (Yaml, Vec<String>) {
+    let mut problems = Vec::new();
+    let yaml = schema(true).convert(doc, keep_unknown, &mut problems);
+
+    (yaml.unwrap_or(Yaml::Null), problems)
+}
+
+impl Kind {
+    /// Validate the given `value` against this kind, and convert it into Yaml.
+    ///
+    /// Problems are added to `problems`, and invalid values are left out.
+    /// Unknown keys are kept as they are if `keep_unknown` is true.
+    fn convert(&self, value: &Marked, keep_unknown: bool, problems: &mut Vec<String>) -> Option<Yaml> {
+        // Any value may be unset
+        if value.is_null() {
+            return Some(Yaml::Null);
+        }
+
+        match (self, &value.node) {
+            (Kind::Str, Node::Scalar(scalar, _)) => Some(Yaml::String(scalar.clone())),
+            (Kind::Bool, Node::Scalar(scalar, _)) => match scalar.trim().to_lowercase().as_str() {
+                "true" | "yes" | "on" => Some(Yaml::Boolean(true)),
+                "false" | "no" | "off" => Some(Yaml::Boolean(false)),
+                _ => {
+                    problems.push(value.error(&format!("Expected true or false, found '{}'", scalar)).to_string());
+                    None
+                },
+            },
+            (Kind::Filter, Node::Scalar(scalar, _)) => match ImgProcParser::parse(scalar) {
+                Ok(_) => Some(Yaml::String(scalar.clone())),
+                Err(err) => {
+                    let message = err.to_string().replace("\n  caused by:", ":");
+                    problems.push(value.error(&message).to_string());
+                    None
+                },
+            },
+            (Kind::List(kind), Node::Seq(items)) => Some(Yaml::Array(
+                items.iter().filter_map(|item| kind.convert(item, keep_unknown, problems)).collect()
+            )),
+            (Kind::List(kind), Node::Scalar(..)) =>
+                kind.convert(value, keep_unknown, problems).map(|item| Yaml::Array(vec![item])),
+            (Kind::Struct(keys), Node::Map(entries)) =>
+                Some(Kind::convert_map(entries, keep_unknown, problems, |name| Kind::find(keys, name))),
+            (Kind::Map(kind), Node::Map(entries)) =>
+                Some(Kind::convert_map(entries, keep_unknown, problems, |_| Ok(&**kind))),
+            _ => {
+                problems.push(value.error(&format!("Expected {}, found {}", self.describe(), value.describe())).to_string());
+                None
+            },
+        }
+    }
+
+    /// Convert the map `entries`, using `find` to get the kind of a value by it's key.
+    ///
+    /// `find` returns the problem if the key is unknown.
+    fn convert_map<'a, F>(entries: &[(Marked, Marked)], keep_unknown: bool, problems: &mut Vec<String>, find: F) -> Yaml
+        where F: Fn(&str) -> ::std::result::Result<&'a Kind, String>
+    {
+        let mut map = BTreeMap::new();
+
+        for (key, value) in entries {
+            let name = match key.as_scalar() {
+                Some(name) => name,
+                None => {
+                    problems.push(key.error(&format!("Expected a key, found {}", key.describe())).to_string());
+                    continue;
+                },
+            };
+
+            // Keys with the append suffix append to a list
+            let append = name.len() > APPEND_SUFFIX.len() && name.ends_with(APPEND_SUFFIX);
+            let base = if append { &name[..name.len() - APPEND_SUFFIX.len()] } else { name };
+            let kind = match find(base) {
+                Ok(kind) => kind,
+                Err(message) => {
+                    problems.push(key.error(&message).to_string());
+                    if keep_unknown {
+                        map.insert(Yaml::String(name.into()), value.to_yaml());
+                    }
+                    continue;
+                },
+            };
+            match *kind {
+                Kind::List(..) => {},
+                _ if append => {
+                    let message = format!("Key '{}' can't be appended to, because it isn't a list", base);
+                    problems.push(key.error(&message).to_string());
+                    continue;
+                },
+                _ => {},
+            }
+            if let Some(value) = kind.convert(value, keep_unknown, problems) {
+                map.insert(Yaml::String(name.into()), value);
+            }
+        }
+
+        Yaml::Hash(map)
+    }
+
+    /// Find the kind of the key with the given `name` in the known `keys`.
+    ///
+    /// If the key is unknown, the problem is returned, suggesting similar keys.
+    fn find<'a>(keys: &'a [(&'static str, Kind)], name: &str) -> ::std::result::Result<&'a Kind, String> {
+        if let Some((_, kind)) = keys.iter().find(|&&(key, _)| key == name) {
+            return Ok(kind);
+        }
+
+        // Suggest similar keys
+        let max_distance = (name.len() / 4).max(1);
+        let mut matches: Vec<(usize, &str)> = keys.iter()
+            .map(|&(key, _)| (util::levenshtein(name, key), key))
+            .filter(|&(distance, _)| distance <= max_distance)
+            .collect();
+        matches.sort();
+        let suggestions: Vec<String> = matches.into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, key)| format!("'{}'", key))
+            .collect();
+
+        Err(match suggestions.len() {
+            0 => format!("Unknown key '{}'", name),
+            _ => format!("Unknown key '{}', did you mean {}?", name, suggestions.join(" or ")),
+        })
+    }
+
+    /// Describe this kind, for use in messages.
+    fn describe(&self) -> &'static str {
+        match *self {
+            Kind::Str => "a string",
+            Kind::Bool => "true or false",
+            Kind::Filter => "a filter",
+            Kind::List(..) => "a list",
+            Kind::Struct(..) | Kind::Map(..) => "a map",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::yaml_rust::{Yaml, YamlLoader};
+
+    use yaml_marked::Marked;
+
+    use super::validate;
+
+    /// Validate the given configuration `source`.
+    fn check(source: &str, keep_unknown: bool) -> (Yaml, Vec<String>) {
+        validate(&Marked::load(source).unwrap().unwrap(), keep_unknown)
+    }
+
+    /// Load the given YAML `source`.
+    fn yaml(source: &str) -> Yaml {
+        YamlLoader::load_from_str(source).unwrap().remove(0)
+    }
+
+    #[test]
+    fn valid() {
+        let (config, problems) = check(
+            "dry: yes\nfilter: blur\nlock: {color: 000000}\nparameter: {radius: 90, clock: true}",
+            false,
+        );
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(config, yaml(
+            "dry: true\nfilter: [blur]\nlock: {color: '000000'}\nparameter: {radius: '90', clock: 'true'}",
+        ));
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let (config, problems) = check("filters: [blur]\ndry: true\nlock: {colr: red}", false);
+        assert_eq!(config, yaml("dry: true\nlock: {}"));
+        assert_eq!(problems, vec![
+            "Unknown key 'filters', did you mean 'filter'? at line 1 column 1",
+            "Unknown key 'colr', did you mean 'color'? at line 3 column 8",
+        ]);
+
+        // Unknown keys may be kept as they are
+        let (config, problems) = check("filters: [blur]\ndry: true", true);
+        assert_eq!(config, yaml("filters: [blur]\ndry: true"));
+        assert_eq!(problems.len(), 1);
+    }
+
+    #[test]
+    fn invalid_values() {
+        let (config, problems) = check(
+            "dry: maybe\nfilter: [blur, nope]\nparameter: {nested: {a: b}, radius: 90}\nlock+: x",
+            true,
+        );
+        assert_eq!(config, yaml("filter: [blur]\nparameter: {radius: '90'}"));
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems[0].starts_with("Expected true or false, found 'maybe' at line 1"));
+        assert!(problems[1].starts_with("Failed to parse filter 'nope'"));
+        assert!(problems[2].starts_with("Expected a string, found a map at line 3"));
+        assert!(problems[3].starts_with("Key 'lock' can't be appended to"));
+    }
+
+    #[test]
+    fn presets() {
+        let (config, problems) = check("presets: {dark: {filter+: invert, preset: x}}", false);
+        assert_eq!(config, yaml("presets: {dark: {filter+: [invert]}}"));
+        // Presets can't select another preset
+        assert_eq!(problems, vec!["Unknown key 'preset' at line 1 column 35"]);
+    }
+}
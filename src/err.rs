@@ -3,115 +3,177 @@ extern crate yaml_rust;
 
 use std;
 use std::convert::From;
+use std::error::Error as StdError;
 use std::fmt::{Display, Formatter};
 use std::num::{ParseFloatError, ParseIntError};
-use std::sync::{PoisonError, RwLockReadGuard, RwLockWriteGuard};
-
-use app;
 
 // Application result type
-pub type Result<'a, T> = std::result::Result<T, Error<'a>>;
-
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The underlying error that caused an error, if known.
+type Cause = Option<Box<StdError>>;
+
+// Process exit codes for each error category
+pub const EXIT_OTHER: i32 = 1;
+pub const EXIT_CONFIG: i32 = 2;
+pub const EXIT_SCREENSHOT: i32 = 3;
+pub const EXIT_FILTER: i32 = 4;
+pub const EXIT_LOCK: i32 = 5;
+
+/// An application error.
+///
+/// Each variant is a category of errors, which is mapped to a distinct process exit code.
+/// An error holds a description, and the underlying error that caused it if there is any.
 #[derive(Debug)]
-pub struct Error<'a> {
-    description: &'a str,
-    cause: Option<&'a std::error::Error>
+pub enum Error {
+    /// Loading or parsing the configuration failed.
+    Config(String, Cause),
+
+    /// Taking a screenshot failed.
+    Screenshot(String, Cause),
+
+    /// Parsing or applying an image filter failed.
+    Filter(String, Cause),
+
+    /// Invoking the lock screen failed.
+    Lock(String, Cause),
+
+    /// Any other error, such as a failing IO operation.
+    Other(String, Cause),
 }
 
-impl<'a> Error<'a> {
-    /// New error instance, with the given `description`.
-    pub fn new(description: &'a str) -> Self {
-        Error {
-            description,
-            cause: None,
-        }
+impl Error {
+    /// New configuration error, with the given `description`.
+    pub fn config<S: Into<String>>(description: S) -> Self {
+        Error::Config(description.into(), None)
     }
 
-    //    /// New error instance, with the given `description` and `cause`.
-    //    pub fn from(description: &'a str, cause: &'a std::error::Error) -> Self {
-    //        Error {
-    //            description,
-    //            cause: Some(cause),
-    //        }
-    //    }
-}
+    /// New screenshot error, with the given `description`.
+    pub fn screenshot<S: Into<String>>(description: S) -> Self {
+        Error::Screenshot(description.into(), None)
+    }
 
-impl<'a> std::error::Error for Error<'a> {
-    fn description(&self) -> &str {
-        self.description
+    /// New filter error, with the given `description`.
+    pub fn filter<S: Into<String>>(description: S) -> Self {
+        Error::Filter(description.into(), None)
     }
 
-    fn cause(&self) -> Option<&std::error::Error> {
-        self.cause
+    /// New lock error, with the given `description`.
+    pub fn lock<S: Into<String>>(description: S) -> Self {
+        Error::Lock(description.into(), None)
     }
-}
 
-impl<'a> Display for Error<'a> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "{} error: {}", app::NAME, self.description)
+    /// New uncategorized error, with the given `description`.
+    pub fn other<S: Into<String>>(description: S) -> Self {
+        Error::Other(description.into(), None)
     }
-}
 
-impl<'a> From<self::config::ConfigError> for Error<'a> {
-    fn from(err: self::config::ConfigError) -> Self {
-        match err {
-            self::config::ConfigError::Frozen => Error::new("The configuration is frozen"),
-            self::config::ConfigError::NotFound(_) => Error::new("Unknown property"),
-            self::config::ConfigError::PathParse(_) => Error::new("The configuration path could not be parsed"),
-            self::config::ConfigError::FileParse { .. } => Error::new("The configuration could not be parsed from a file"),
-            self::config::ConfigError::Type { .. } => Error::new("The property value could not be parsed into the requested file"),
-            self::config::ConfigError::Message(_) => Error::new("An error occurred while using the configuration"),
-            self::config::ConfigError::Foreign(_) => Error::new("An unadorned error from foreign origin occurred, causing problems with the configuration")
+    /// Set the underlying error that caused this error, and return it.
+    pub fn caused_by<E: StdError + 'static>(mut self, err: E) -> Self {
+        *self.cause_mut() = Some(Box::new(err));
+        self
+    }
+
+    /// Get the description of this error, without it's causes.
+    pub fn message(&self) -> &str {
+        match *self {
+            Error::Config(ref description, _)
+            | Error::Screenshot(ref description, _)
+            | Error::Filter(ref description, _)
+            | Error::Lock(ref description, _)
+            | Error::Other(ref description, _) => description,
+        }
+    }
+
+    /// Get the process exit code for the category of this error.
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            Error::Config(..) => EXIT_CONFIG,
+            Error::Screenshot(..) => EXIT_SCREENSHOT,
+            Error::Filter(..) => EXIT_FILTER,
+            Error::Lock(..) => EXIT_LOCK,
+            Error::Other(..) => EXIT_OTHER,
+        }
+    }
+
+    /// Get a reference to the underlying error that caused this error.
+    fn cause_ref(&self) -> &Cause {
+        match *self {
+            Error::Config(_, ref cause)
+            | Error::Screenshot(_, ref cause)
+            | Error::Filter(_, ref cause)
+            | Error::Lock(_, ref cause)
+            | Error::Other(_, ref cause) => cause,
+        }
+    }
+
+    /// Get a mutable reference to the underlying error that caused this error.
+    fn cause_mut(&mut self) -> &mut Cause {
+        match *self {
+            Error::Config(_, ref mut cause)
+            | Error::Screenshot(_, ref mut cause)
+            | Error::Filter(_, ref mut cause)
+            | Error::Lock(_, ref mut cause)
+            | Error::Other(_, ref mut cause) => cause,
         }
     }
 }
 
-impl<'a> From<PoisonError<self::config::ConfigError>> for Error<'a> {
-    fn from(_: PoisonError<self::config::ConfigError>) -> Self {
-        Error::new("Configuration is poisoned")
+impl StdError for Error {
+    fn description(&self) -> &str {
+        self.message()
+    }
+
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        self.cause_ref().as_ref().map(|cause| &**cause)
     }
 }
 
-impl<'a> From<PoisonError<RwLockReadGuard<'a, config::Config>>> for Error<'a> {
-    fn from(_: PoisonError<RwLockReadGuard<'a, config::Config>>) -> Self {
-        Error::new("The read guard of this configuration is poisoned")
+impl Display for Error {
+    /// Format the error along with the full chain of errors that caused it.
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message())?;
+
+        // Describe each cause, only use the message of our own errors as we walk their causes
+        let mut cause = self.source();
+        while let Some(err) = cause {
+            match err.downcast_ref::<Error>() {
+                Some(err) => write!(f, "\n  caused by: {}", err.message())?,
+                None => write!(f, "\n  caused by: {}", err)?,
+            }
+            cause = err.source();
+        }
+
+        Ok(())
     }
 }
 
-impl<'a> From<PoisonError<RwLockWriteGuard<'a, config::Config>>> for Error<'a> {
-    fn from(_: PoisonError<RwLockWriteGuard<'a, config::Config>>) -> Self {
-        Error::new("The write guard of this configuratin is poisoned")
+impl From<self::config::ConfigError> for Error {
+    fn from(err: self::config::ConfigError) -> Self {
+        Error::config("An error occurred while using the configuration").caused_by(err)
     }
 }
 
-impl<'a> From<yaml_rust::ScanError> for Error<'a> {
-    fn from(_: yaml_rust::ScanError) -> Self {
-//        use std::error::Error;
-//        super::Error::new(err.description())
-        Error::new("Failed to parse YAML configuration file")
+impl From<yaml_rust::ScanError> for Error {
+    fn from(err: yaml_rust::ScanError) -> Self {
+        Error::config("Failed to parse YAML configuration").caused_by(err)
     }
 }
 
-impl<'a> From<std::io::Error> for Error<'a> {
-    fn from(_: std::io::Error) -> Self {
-//        use std::error::Error;
-//        super::Error::new(err.description())
-        Error::new("IO operation failed")
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::other("IO operation failed").caused_by(err)
     }
 }
 
-impl<'a> From<ParseIntError> for Error<'a> {
-    fn from(_: ParseIntError) -> Self {
-        //        use std::error::Error;
-        //        super::Error::new(err.description())
-        Error::new("Unable to parse float value")
+impl From<ParseIntError> for Error {
+    fn from(err: ParseIntError) -> Self {
+        Error::other("Unable to parse integer value").caused_by(err)
     }
 }
 
-impl<'a> From<ParseFloatError> for Error<'a> {
-    fn from(_: ParseFloatError) -> Self {
-        //        use std::error::Error;
-        //        super::Error::new(err.description())
-        Error::new("Unable to parse float value")
+impl From<ParseFloatError> for Error {
+    fn from(err: ParseFloatError) -> Self {
+        Error::other("Unable to parse float value").caused_by(err)
     }
 }
@@ -0,0 +1,239 @@
+extern crate image;
+
+use self::image::Rgba;
+
+use err::{Error, Result};
+use util;
+
+/// Named colors, with their hexadecimal value.
+static NAMED: &'static [(&'static str, &'static str)] = &[
+    ("black", "000000"),
+    ("blue", "0000ff"),
+    ("cyan", "00ffff"),
+    ("gray", "808080"),
+    ("green", "008000"),
+    ("grey", "808080"),
+    ("lime", "00ff00"),
+    ("magenta", "ff00ff"),
+    ("maroon", "800000"),
+    ("navy", "000080"),
+    ("olive", "808000"),
+    ("orange", "ffa500"),
+    ("purple", "800080"),
+    ("red", "ff0000"),
+    ("silver", "c0c0c0"),
+    ("teal", "008080"),
+    ("transparent", "00000000"),
+    ("white", "ffffff"),
+    ("yellow", "ffff00"),
+];
+
+/// An RGBA color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Construct a new color from the given channels.
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// Parse a color from the given `value`.
+    ///
+    /// The following formats are supported:
+    /// - `#rgb`, `#rrggbb` and `#rrggbbaa`, where the `#` prefix is optional
+    /// - `rgb(r, g, b)` with channels from 0 to 255
+    /// - `rgba(r, g, b, a)` with the alpha channel from 0 to 1
+    /// - a color name, such as `black`, `white` or `transparent`
+    ///
+    /// An error is returned if the value could not be parsed.
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim().to_lowercase();
+
+        // Parse named colors
+        if let Some(&(_, hex)) = NAMED.iter().find(|named| named.0 == value) {
+            return Color::parse_hex(hex);
+        }
+
+        // Parse functional notations
+        if value.starts_with("rgba(") && value.ends_with(')') {
+            return Color::parse_rgb(&value[5..value.len() - 1], true);
+        }
+        if value.starts_with("rgb(") && value.ends_with(')') {
+            return Color::parse_rgb(&value[4..value.len() - 1], false);
+        }
+
+        Color::parse_hex(&value)
+    }
+
+    /// Parse a color from the given hexadecimal `value`.
+    ///
+    /// The `rgb`, `rrggbb` and `rrggbbaa` formats are supported, optionally prefixed with a `#`.
+    ///
+    /// An error is returned if the value could not be parsed.
+    fn parse_hex(value: &str) -> Result<Self> {
+        // Strip the optional hash prefix
+        let hex = if value.starts_with('#') { value.split_at(1).1 } else { value };
+
+        // Make sure the hexadecimal value has a supported length
+        if (hex.len() != 3 && hex.len() != 6 && hex.len() != 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::filter(
+                "Invalid color, use #rgb, #rrggbb, #rrggbbaa, rgb(r, g, b), rgba(r, g, b, a) or a color name"
+            ));
+        }
+
+        // Expand the short notation, by repeating each digit
+        if hex.len() == 3 {
+            let hex: String = hex.chars().flat_map(|c| vec![c, c]).collect();
+            return Color::parse_hex(&hex);
+        }
+
+        // Parse each channel
+        let channel = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+        Ok(Color::new(
+            channel(0),
+            channel(1),
+            channel(2),
+            if hex.len() == 8 { channel(3) } else { 255 },
+        ))
+    }
+
+    /// Parse the comma separated channels of the `rgb(...)` or `rgba(...)` notation,
+    /// given as `value`. If `alpha` is true, a fourth alpha channel from 0 to 1 is expected.
+    ///
+    /// An error is returned if the channels could not be parsed.
+    fn parse_rgb(value: &str, alpha: bool) -> Result<Self> {
+        // Split the channels
+        let channels: Vec<&str> = value.split(',').map(|channel| channel.trim()).collect();
+        if channels.len() != if alpha { 4 } else { 3 } {
+            return Err(Error::filter("Invalid color, rgb() takes 3 and rgba() takes 4 channels"));
+        }
+
+        // Parse the color channels
+        let mut rgb = [0u8; 3];
+        for (out, channel) in rgb.iter_mut().zip(channels.iter()) {
+            *out = channel.parse::<u8>()
+                .map_err(|err| Error::filter("Invalid color, rgb() channels must be from 0 to 255").caused_by(err))?;
+        }
+
+        // Parse the alpha channel
+        let a = if alpha {
+            let a = channels[3].parse::<f32>()
+                .map_err(|err| Error::filter("Invalid color, the rgba() alpha channel must be from 0 to 1").caused_by(err))?;
+            // Clamping changes values out of range, and NaN never equals itself
+            if util::clamp(a, 0f32, 1f32) != a {
+                return Err(Error::filter("Invalid color, the rgba() alpha channel must be from 0 to 1"));
+            }
+            (a * 255f32).round() as u8
+        } else {
+            255
+        };
+
+        Ok(Color::new(rgb[0], rgb[1], rgb[2], a))
+    }
+
+    /// Format the color as `#rrggbbaa` hexadecimal string.
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    /// Get the color as `Rgba` pixel, that is used by the image crate.
+    pub fn to_rgba(self) -> Rgba<u8> {
+        Rgba([self.r, self.g, self.b, self.a])
+    }
+}
+
+/// Blend the `top` pixel over the `bottom` pixel, with an additional `opacity` from 0 to 1.
+pub fn blend(bottom: &Rgba<u8>, top: &Rgba<u8>, opacity: f32) -> Rgba<u8> {
+    // Determine the alpha of both pixels, and the resulting alpha
+    let top_alpha = top.data[3] as f32 / 255f32 * opacity;
+    let bottom_alpha = bottom.data[3] as f32 / 255f32;
+    let alpha = top_alpha + bottom_alpha * (1f32 - top_alpha);
+    if alpha <= 0f32 {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    // Blend each color channel
+    let mut out = [0u8; 4];
+    for (i, channel) in out.iter_mut().enumerate().take(3) {
+        *channel = ((top.data[i] as f32 * top_alpha
+            + bottom.data[i] as f32 * bottom_alpha * (1f32 - top_alpha))
+            / alpha)
+            .round() as u8;
+    }
+    out[3] = (alpha * 255f32).round() as u8;
+
+    Rgba(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::image::Rgba;
+    use super::{blend, Color};
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(Color::parse("#ff8000").unwrap(), Color::new(255, 128, 0, 255));
+        assert_eq!(Color::parse("FF8000").unwrap(), Color::new(255, 128, 0, 255));
+        assert_eq!(Color::parse("#ff800080").unwrap(), Color::new(255, 128, 0, 128));
+        assert_eq!(Color::parse("#f80").unwrap(), Color::new(255, 136, 0, 255));
+        assert_eq!(Color::parse(" 000000 ").unwrap(), Color::new(0, 0, 0, 255));
+    }
+
+    #[test]
+    fn parse_rgb() {
+        assert_eq!(Color::parse("rgb(255, 128, 0)").unwrap(), Color::new(255, 128, 0, 255));
+        assert_eq!(Color::parse("RGB(1,2,3)").unwrap(), Color::new(1, 2, 3, 255));
+        assert_eq!(Color::parse("rgba(255, 128, 0, 0.5)").unwrap(), Color::new(255, 128, 0, 128));
+        assert_eq!(Color::parse("rgba(0, 0, 0, 0)").unwrap(), Color::new(0, 0, 0, 0));
+        assert_eq!(Color::parse("rgba(0, 0, 0, 1)").unwrap(), Color::new(0, 0, 0, 255));
+    }
+
+    #[test]
+    fn parse_named() {
+        assert_eq!(Color::parse("white").unwrap(), Color::new(255, 255, 255, 255));
+        assert_eq!(Color::parse("Orange").unwrap(), Color::new(255, 165, 0, 255));
+        assert_eq!(Color::parse("transparent").unwrap(), Color::new(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for value in &[
+            "", "#", "#ff", "#ff80", "#ff800", "#ff80000", "#gg8000", "##ff8000", "unknown",
+            "rgb(255, 128)", "rgb(256, 0, 0)", "rgb(-1, 0, 0)", "rgb(0, 0, 0, 1)",
+            "rgba(0, 0, 0)", "rgba(0, 0, 0, 1.5)", "rgba(0, 0, 0, -0.1)", "rgba(0, 0, 0, nan)",
+        ] {
+            assert!(Color::parse(value).is_err(), "'{}' should be invalid", value);
+        }
+    }
+
+    #[test]
+    fn to_hex() {
+        assert_eq!(Color::new(255, 128, 0, 255).to_hex(), "#ff8000ff");
+        assert_eq!(Color::parse(&Color::new(1, 2, 3, 4).to_hex()).unwrap(), Color::new(1, 2, 3, 4));
+    }
+
+    #[test]
+    fn blend_opaque() {
+        let bottom = Rgba([0, 0, 255, 255]);
+        let top = Rgba([255, 0, 0, 255]);
+        assert_eq!(blend(&bottom, &top, 1f32), top);
+        assert_eq!(blend(&bottom, &top, 0f32), bottom);
+        assert_eq!(blend(&bottom, &top, 0.5), Rgba([128, 0, 128, 255]));
+    }
+
+    #[test]
+    fn blend_transparent() {
+        let red = Rgba([255, 0, 0, 255]);
+        let clear = Rgba([0, 0, 0, 0]);
+        assert_eq!(blend(&red, &clear, 1f32), red);
+        assert_eq!(blend(&clear, &red, 1f32), red);
+        assert_eq!(blend(&clear, &clear, 1f32), clear);
+        assert_eq!(blend(&clear, &red, 0.5), Rgba([255, 0, 0, 128]));
+    }
+}
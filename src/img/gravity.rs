@@ -0,0 +1,75 @@
+use err::{Error, Result};
+
+/// Names of all gravities, as accepted by `Gravity::parse`.
+pub const GRAVITIES: &'static [&'static str] = &[
+    "center", "top", "top-right", "right", "bottom-right", "bottom", "bottom-left", "left", "top-left",
+];
+
+/// Gravity, defining where an item is placed on a canvas.
+///
+/// This is used by processors that draw something on top of an image,
+/// such as an overlay or text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gravity {
+    Center,
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+    TopLeft,
+}
+
+impl Gravity {
+    /// Parse the given gravity `name`.
+    ///
+    /// The name is one of `GRAVITIES`, it is case-insensitive and is trimmed.
+    ///
+    /// If the name is unknown, an error is returned.
+    pub fn parse(name: &str) -> Result<Gravity> {
+        match name.trim().to_lowercase().as_str() {
+            "center" => Ok(Gravity::Center),
+            "top" => Ok(Gravity::Top),
+            "top-right" => Ok(Gravity::TopRight),
+            "right" => Ok(Gravity::Right),
+            "bottom-right" => Ok(Gravity::BottomRight),
+            "bottom" => Ok(Gravity::Bottom),
+            "bottom-left" => Ok(Gravity::BottomLeft),
+            "left" => Ok(Gravity::Left),
+            "top-left" => Ok(Gravity::TopLeft),
+            _ => Err(Error::filter(format!("Unknown gravity, use one of: {}", GRAVITIES.join(", ")))),
+        }
+    }
+
+    /// Determine the top-left position of an item with the size `item` on a canvas with the size
+    /// `canvas`, both given as `(width, height)`.
+    ///
+    /// The `offset` moves the item away from the edge it is attached to, so a positive offset
+    /// always moves the item inwards. For centered axes the offset moves it right or down.
+    ///
+    /// The returned position may be negative or outside the canvas if the item doesn't fit.
+    pub fn position(&self, canvas: (u32, u32), item: (u32, u32), offset: (i32, i32)) -> (i64, i64) {
+        // Determine the free space on both axes
+        let free_x = canvas.0 as i64 - item.0 as i64;
+        let free_y = canvas.1 as i64 - item.1 as i64;
+        let (offset_x, offset_y) = (offset.0 as i64, offset.1 as i64);
+
+        // Position on the horizontal axis
+        let x = match *self {
+            Gravity::TopLeft | Gravity::Left | Gravity::BottomLeft => offset_x,
+            Gravity::Top | Gravity::Center | Gravity::Bottom => free_x / 2 + offset_x,
+            Gravity::TopRight | Gravity::Right | Gravity::BottomRight => free_x - offset_x,
+        };
+
+        // Position on the vertical axis
+        let y = match *self {
+            Gravity::TopLeft | Gravity::Top | Gravity::TopRight => offset_y,
+            Gravity::Left | Gravity::Center | Gravity::Right => free_y / 2 + offset_y,
+            Gravity::BottomLeft | Gravity::Bottom | Gravity::BottomRight => free_y - offset_y,
+        };
+
+        (x, y)
+    }
+}
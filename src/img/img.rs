@@ -1,10 +1,25 @@
+extern crate chrono;
 extern crate image;
 
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use err::Result;
+use self::chrono::{Datelike, Local};
+
+use err::{Error, Result};
 use img::ImgEdit;
 
+// Methods for picking an image from a directory
+pub static PICK_RANDOM: &'static str = "random";
+pub static PICK_DAILY: &'static str = "daily";
+
+/// File extensions of images that may be picked from a directory.
+static EXTENSIONS: &'static [&'static str] = &[
+    "bmp", "gif", "ico", "jpeg", "jpg", "png", "ppm", "tga", "tif", "tiff", "webp",
+];
+
 /// Image struct.
 pub struct Img {
     file: PathBuf
@@ -21,6 +36,55 @@ impl Img {
 //        Self::new(&PathBuf::from(file))
 //    }
 
+    /// Select the image at the given `path`.
+    ///
+    /// If the path is a directory, an image inside it is picked using the given `pick` method.
+    /// This may be `random` to pick a random image each time, or `daily` to pick a different
+    /// image each day.
+    ///
+    /// An error is returned if the path doesn't exist, or if no image could be picked.
+    pub fn select(path: &Path, pick: &str) -> Result<Self> {
+        if path.is_file() {
+            return Ok(Img::new(&path.to_path_buf()));
+        }
+        if !path.is_dir() {
+            return Err(Error::other(format!("The image path does not exist: {}", path.display())));
+        }
+
+        // List the images in the directory, sort them for a stable order
+        let mut images: Vec<PathBuf> = fs::read_dir(path)
+            .map_err(|err| Error::other(format!("Failed to read image directory {:?}", path)).caused_by(err))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+                .unwrap_or(false)
+            )
+            .collect();
+        if images.is_empty() {
+            return Err(Error::other(format!("No images found in the image directory: {}", path.display())));
+        }
+        images.sort();
+
+        // Determine the index of the image to pick
+        let pick = pick.trim().to_lowercase();
+        let index = if pick == PICK_RANDOM {
+            let time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.subsec_nanos() as u64 ^ time.as_secs())
+                .unwrap_or(0);
+            (time ^ process::id() as u64) as usize % images.len()
+        } else if pick == PICK_DAILY {
+            Local::now().num_days_from_ce() as usize % images.len()
+        } else {
+            return Err(Error::other("Unknown image pick method, use random or daily"));
+        };
+
+        Ok(Img::new(&images[index]))
+    }
+
     pub fn edit(&self) -> Result<ImgEdit> {
         ImgEdit::load(self)
     }
@@ -1,8 +1,9 @@
 extern crate image;
 
 use std::fs::File;
+use std::path::Path;
 
-use self::image::DynamicImage;
+use self::image::{DynamicImage, GenericImage, ImageFormat};
 
 use err::{Error, Result};
 use img::Img;
@@ -25,9 +26,9 @@ impl ImgEdit {
         // Load the image
         match image::open(img.path_buf()) {
             Ok(load) => Ok(Self::from(load)),
-            Err(_) => Err(Error::new(
-                "Failed to load image for editing"
-            ))
+            Err(err) => Err(Error::other(
+                format!("Failed to load image {:?} for editing", img.path_buf())
+            ).caused_by(err))
         }
     }
 
@@ -38,20 +39,84 @@ impl ImgEdit {
         self.img
     }
 
+    /// Get the dimensions of the image as `(width, height)`.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.img.dimensions()
+    }
+
+    /// Cut out a copy of the part of the image delimited by the given bounding rectangle.
+    pub fn crop(&mut self, x: u32, y: u32, width: u32, height: u32) -> ImgEdit {
+        ImgEdit::from(self.img.crop(x, y, width, height))
+    }
+
+    /// Paste the given `other` image into this image, with it's top-left corner at `x` and `y`.
+    ///
+    /// An error is returned if the other image doesn't fit.
+    pub fn paste(&mut self, other: &ImgEdit, x: u32, y: u32) -> Result<()> {
+        if self.img.copy_from(&other.img, x, y) {
+            Ok(())
+        } else {
+            Err(Error::other("Failed to paste image, it doesn't fit"))
+        }
+    }
+
+    /// Get the raw pixel data of the image in BGRX format, row by row from the top-left corner.
+    ///
+    /// Each pixel takes 4 bytes: blue, green, red and an unused byte.
+    /// This is the native pixel format of most X11 screens, and can be read by i3lock directly.
+    pub fn to_bgrx(&self) -> Vec<u8> {
+        let rgba = self.img.to_rgba();
+        let mut data = Vec::with_capacity(rgba.len());
+        for pixel in rgba.chunks(4) {
+            data.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 0xff]);
+        }
+        data
+    }
+
     /// Save the edited image.
+    ///
+    /// The image format is determined by the file extension, see `ImgEdit::format_for`.
     pub fn save(self, img: &Img) -> Result<File> {
+        // Determine the image format
+        let format = ImgEdit::format_for(img.path_buf())?;
+
         // Open target file
-        let mut img_file = File::create(&img.path_buf());
-        if img_file.is_err() {
-            return Err(Error::new("Failed create file to save the processed image to"));
-        }
+        let mut img_file = File::create(&img.path_buf())
+            .map_err(|err| Error::other(
+                format!("Failed create file {:?} to save the processed image to", img.path_buf())
+            ).caused_by(err))?;
+
+        // Formats without transparency support must be saved without alpha channel
+        let img = match format {
+            ImageFormat::PNG | ImageFormat::GIF | ImageFormat::ICO => self.img,
+            _ => DynamicImage::ImageRgb8(self.img.to_rgb()),
+        };
 
         // Save the image
-        let img_out = self.img.save(img_file.as_mut().unwrap(), image::PNG);
-        if img_out.is_err() {
-            return Err(Error::new("Failed to save processed image"));
-        }
+        img.save(&mut img_file, format)
+            .map_err(|err| Error::other("Failed to save processed image").caused_by(err))?;
 
-        Ok(img_file.unwrap())
+        Ok(img_file)
+    }
+
+    /// Determine the image format to save the given `path` in, by it's file extension.
+    ///
+    /// PNG is used if the path has no extension.
+    /// An error is returned if saving images with the extension isn't supported.
+    pub fn format_for(path: &Path) -> Result<ImageFormat> {
+        let ext = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => ext.to_lowercase(),
+            None => return Ok(ImageFormat::PNG),
+        };
+
+        match ext.as_str() {
+            "png" => Ok(ImageFormat::PNG),
+            "jpg" | "jpeg" => Ok(ImageFormat::JPEG),
+            "bmp" => Ok(ImageFormat::BMP),
+            "gif" => Ok(ImageFormat::GIF),
+            "ico" => Ok(ImageFormat::ICO),
+            "ppm" => Ok(ImageFormat::PPM),
+            _ => Err(Error::other("Unsupported image format, use png, jpg, bmp, gif, ico or ppm")),
+        }
     }
 }
@@ -1,25 +1,35 @@
+extern crate image;
+
+use std::cmp;
 use std::collections::HashMap;
 
+use self::image::{DynamicImage, GenericImage, ImageBuffer, Rgba, RgbaImage};
+
 use err::Result;
 use img::ImgEdit;
 
-use super::{ImgProc, Prop};
+use super::{ImgProc, Prop, Property};
 
 // Property names
 pub static PROP_SIGMA: &'static str = "sigma";
+pub static PROP_FAST: &'static str = "fast";
+
+/// The blurring sigma to aim for on the downscaled image, when fast blurring.
+static FAST_SIGMA: f32 = 1.25;
 
 // Default properties
 lazy_static! {
-    static ref PROPERTIES: HashMap<&'static str, Prop> = {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
         let mut map = HashMap::new();
-        map.insert(PROP_SIGMA, Prop::Float(Some(5.0)));
+        map.insert(PROP_SIGMA, Property::new(Prop::Float(Some(5.0)), "Blurring intensity.").min(0.0));
+        map.insert(PROP_FAST, Property::new(Prop::Bool(Some(false)), "Blur a downscaled copy of the image, which is much faster on large images."));
         map
     };
 }
 
 /// Image blurring processor.
 pub struct Blur {
-    properties: HashMap<&'static str, Prop>
+    properties: HashMap<&'static str, Property>
 }
 
 impl Blur {
@@ -28,22 +38,204 @@ impl Blur {
             properties: PROPERTIES.clone()
         }
     }
+
+    /// Blur the given `img` with the given `sigma` in a fast way.
+    ///
+    /// The image is downscaled first, then blurred with a smaller sigma and then upscaled again
+    /// to it's original size. This is much faster for large images and big sigmas,
+    /// while the result looks nearly the same.
+    fn fast_blur(img: DynamicImage, sigma: f32) -> DynamicImage {
+        // Determine the downscaling factor, don't downscale if blurring is cheap already
+        let factor = (sigma / FAST_SIGMA).floor() as u32;
+        if factor < 2 {
+            return img.blur(sigma);
+        }
+
+        // Nothing to blur on an empty image
+        let (width, height) = img.dimensions();
+        if width == 0 || height == 0 {
+            return img;
+        }
+
+        // Downscale, blur and upscale
+        let small = DynamicImage::ImageRgba8(Blur::downscale(&img.to_rgba(), factor))
+            .blur(sigma / factor as f32)
+            .to_rgba();
+        DynamicImage::ImageRgba8(Blur::upscale(&small, factor, width, height))
+    }
+
+    /// Downscale the given `img` by the given integer `factor`,
+    /// by averaging each block of `factor` by `factor` pixels into a single pixel.
+    fn downscale(img: &RgbaImage, factor: u32) -> RgbaImage {
+        let (width, height) = img.dimensions();
+
+        ImageBuffer::from_fn(
+            div_ceil(width, factor),
+            div_ceil(height, factor),
+            |x, y| {
+                // Sum all pixels in the block
+                let mut sum = [0u32; 4];
+                let (left, top) = (x * factor, y * factor);
+                let (right, bottom) = (cmp::min(left + factor, width), cmp::min(top + factor, height));
+                for py in top..bottom {
+                    for px in left..right {
+                        let pixel = img.get_pixel(px, py);
+                        for (channel, value) in sum.iter_mut().zip(pixel.data.iter()) {
+                            *channel += *value as u32;
+                        }
+                    }
+                }
+
+                // Average the block
+                let count = (right - left) * (bottom - top);
+                Rgba([
+                    (sum[0] / count) as u8,
+                    (sum[1] / count) as u8,
+                    (sum[2] / count) as u8,
+                    (sum[3] / count) as u8,
+                ])
+            },
+        )
+    }
+
+    /// Upscale the given `img` that was downscaled by `factor` back to the given `width` and
+    /// `height`, using bilinear interpolation.
+    fn upscale(img: &RgbaImage, factor: u32, width: u32, height: u32) -> RgbaImage {
+        let (small_width, small_height) = img.dimensions();
+        if small_width == 0 || small_height == 0 {
+            return RgbaImage::new(width, height);
+        }
+
+        // Find the two source pixels and the weight of the second one, for a target coordinate
+        let sample = |target: u32, size: u32| -> (u32, u32, f32) {
+            let source = ((target as f32 + 0.5) / factor as f32 - 0.5).max(0f32);
+            let first = cmp::min(source.floor() as u32, size - 1);
+            let second = cmp::min(first + 1, size - 1);
+            (first, second, source - first as f32)
+        };
+        let columns: Vec<(u32, u32, f32)> = (0..width).map(|x| sample(x, small_width)).collect();
+
+        let mut out = RgbaImage::new(width, height);
+        for y in 0..height {
+            let (top, bottom, weight_y) = sample(y, small_height);
+            for (x, &(left, right, weight_x)) in columns.iter().enumerate() {
+                // Interpolate each channel between the four surrounding pixels
+                let (a, b) = (img.get_pixel(left, top), img.get_pixel(right, top));
+                let (c, d) = (img.get_pixel(left, bottom), img.get_pixel(right, bottom));
+                let mut pixel = [0u8; 4];
+                for (i, channel) in pixel.iter_mut().enumerate() {
+                    let upper = a.data[i] as f32 + (b.data[i] as f32 - a.data[i] as f32) * weight_x;
+                    let lower = c.data[i] as f32 + (d.data[i] as f32 - c.data[i] as f32) * weight_x;
+                    *channel = (upper + (lower - upper) * weight_y).round() as u8;
+                }
+                out.put_pixel(x as u32, y, Rgba(pixel));
+            }
+        }
+
+        out
+    }
+}
+
+/// Divide `value` by `divisor`, rounding up.
+fn div_ceil(value: u32, divisor: u32) -> u32 {
+    let quotient = value / divisor;
+    if quotient * divisor < value { quotient + 1 } else { quotient }
 }
 
 impl ImgProc for Blur {
     fn process(&self, img: ImgEdit) -> Result<ImgEdit> {
+        let sigma = self.property(PROP_SIGMA).unwrap().as_float().unwrap();
+
         // TODO: Handle errors!
-        Ok(ImgEdit::from(
-            img.into_img()
-                .blur(self.property(PROP_SIGMA).unwrap().as_float().unwrap())
-        ))
+        if self.property(PROP_FAST).unwrap().as_bool().unwrap_or(false) {
+            Ok(ImgEdit::from(Blur::fast_blur(img.into_img(), sigma)))
+        } else {
+            Ok(ImgEdit::from(img.into_img().blur(sigma)))
+        }
     }
 
-    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Prop> {
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
         &self.properties
     }
 
-    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Prop> {
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
         &mut self.properties
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::image::{DynamicImage, GenericImage, Rgba, RgbaImage};
+    use super::{div_ceil, Blur};
+
+    #[test]
+    fn div_ceil_rounds_up() {
+        assert_eq!(div_ceil(0, 4), 0);
+        assert_eq!(div_ceil(8, 4), 2);
+        assert_eq!(div_ceil(9, 4), 3);
+        assert_eq!(div_ceil(1, 4), 1);
+    }
+
+    #[test]
+    fn downscale_averages_blocks() {
+        // A 5x3 image with non-divisible sizes, leaving partial blocks at the edges
+        let img = RgbaImage::from_fn(5, 3, |x, y| {
+            let value = (x * 10 + y * 50) as u8;
+            Rgba([value, value, value, 255])
+        });
+        let small = Blur::downscale(&img, 2);
+        assert_eq!(small.dimensions(), (3, 2));
+        assert_eq!(small.get_pixel(0, 0).data, [30, 30, 30, 255]);
+        assert_eq!(small.get_pixel(2, 0).data, [65, 65, 65, 255]);
+        assert_eq!(small.get_pixel(2, 1).data, [140, 140, 140, 255]);
+    }
+
+    #[test]
+    fn upscale_keeps_dimensions() {
+        let small = RgbaImage::from_pixel(3, 2, Rgba([20, 40, 60, 255]));
+        let img = Blur::upscale(&small, 2, 5, 3);
+        assert_eq!(img.dimensions(), (5, 3));
+        assert!(img.pixels().all(|pixel| pixel.data == [20, 40, 60, 255]));
+    }
+
+    #[test]
+    fn upscale_interpolates() {
+        let small = RgbaImage::from_fn(2, 1, |x, _| {
+            let value = (x * 100) as u8;
+            Rgba([value, value, value, 255])
+        });
+        let img = Blur::upscale(&small, 2, 4, 2);
+        assert_eq!(img.get_pixel(0, 0).data, [0, 0, 0, 255]);
+        assert_eq!(img.get_pixel(1, 0).data, [25, 25, 25, 255]);
+        assert_eq!(img.get_pixel(2, 1).data, [75, 75, 75, 255]);
+        assert_eq!(img.get_pixel(3, 1).data, [100, 100, 100, 255]);
+    }
+
+    #[test]
+    fn upscale_empty() {
+        let img = Blur::upscale(&RgbaImage::new(0, 0), 2, 0, 0);
+        assert_eq!(img.dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn fast_blur_keeps_dimensions() {
+        let img = RgbaImage::from_pixel(37, 23, Rgba([80, 120, 160, 255]));
+        let out = Blur::fast_blur(DynamicImage::ImageRgba8(img), 10.0);
+        assert_eq!(out.dimensions(), (37, 23));
+        assert_eq!(out.get_pixel(18, 11).data, [80, 120, 160, 255]);
+    }
+
+    #[test]
+    fn fast_blur_single_pixel() {
+        let img = RgbaImage::from_pixel(1, 1, Rgba([10, 20, 30, 255]));
+        let out = Blur::fast_blur(DynamicImage::ImageRgba8(img), 10.0);
+        assert_eq!(out.dimensions(), (1, 1));
+        assert_eq!(out.get_pixel(0, 0).data, [10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn fast_blur_empty() {
+        let out = Blur::fast_blur(DynamicImage::ImageRgba8(RgbaImage::new(0, 0)), 10.0);
+        assert_eq!(out.dimensions(), (0, 0));
+    }
+}
@@ -3,23 +3,26 @@ use std::collections::HashMap;
 use err::Result;
 use img::ImgEdit;
 
-use super::{ImgProc, Prop};
+use super::{ImgProc, Prop, Property};
 
 // Property names
 pub static PROP_AMOUNT: &'static str = "amount";
 
 // Default properties
 lazy_static! {
-    static ref PROPERTIES: HashMap<&'static str, Prop> = {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
         let mut map = HashMap::new();
-        map.insert(PROP_AMOUNT, Prop::Int(None));
+        map.insert(PROP_AMOUNT, Property::new(Prop::Int(None), "Amount to brighten each pixel by, negative values darken the image.")
+            .required()
+            .min(-255.0)
+            .max(255.0));
         map
     };
 }
 
 /// Image brighten processor.
 pub struct Brighten {
-    properties: HashMap<&'static str, Prop>
+    properties: HashMap<&'static str, Property>
 }
 
 impl Brighten {
@@ -39,11 +42,11 @@ impl ImgProc for Brighten {
         ))
     }
 
-    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Prop> {
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
         &self.properties
     }
 
-    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Prop> {
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
         &mut self.properties
     }
 }
\ No newline at end of file
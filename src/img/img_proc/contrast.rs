@@ -3,23 +3,25 @@ use std::collections::HashMap;
 use err::Result;
 use img::ImgEdit;
 
-use super::{ImgProc, Prop};
+use super::{ImgProc, Prop, Property};
 
 // Property names
 pub static PROP_AMOUNT: &'static str = "amount";
 
 // Default properties
 lazy_static! {
-    static ref PROPERTIES: HashMap<&'static str, Prop> = {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
         let mut map = HashMap::new();
-        map.insert(PROP_AMOUNT, Prop::Float(None));
+        map.insert(PROP_AMOUNT, Property::new(Prop::Float(None), "Amount to adjust the contrast by, negative values decrease the contrast.")
+            .required()
+            .min(-100.0));
         map
     };
 }
 
 /// Image contrast processor.
 pub struct Contrast {
-    properties: HashMap<&'static str, Prop>
+    properties: HashMap<&'static str, Property>
 }
 
 impl Contrast {
@@ -39,11 +41,11 @@ impl ImgProc for Contrast {
         ))
     }
 
-    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Prop> {
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
         &self.properties
     }
 
-    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Prop> {
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
         &mut self.properties
     }
 }
\ No newline at end of file
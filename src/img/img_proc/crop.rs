@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
-use err::Result;
+use err::{Error, Result};
 use img::ImgEdit;
 
-use super::{ImgProc, Prop};
+use super::{ImgProc, Prop, Property};
 
 // Property names
 pub static PROP_X: &'static str = "x";
@@ -13,19 +13,19 @@ pub static PROP_HEIGHT: &'static str = "height";
 
 // Default properties
 lazy_static! {
-    static ref PROPERTIES: HashMap<&'static str, Prop> = {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
         let mut map = HashMap::new();
-        map.insert(PROP_X, Prop::UInt(None));
-        map.insert(PROP_Y, Prop::UInt(None));
-        map.insert(PROP_WIDTH, Prop::UInt(None));
-        map.insert(PROP_HEIGHT, Prop::UInt(None));
+        map.insert(PROP_X, Property::new(Prop::Length(None), "Left edge of the region.").required().min(0.0));
+        map.insert(PROP_Y, Property::new(Prop::Length(None), "Top edge of the region.").required().min(0.0));
+        map.insert(PROP_WIDTH, Property::new(Prop::Length(None), "Width of the region.").required().min(0.0));
+        map.insert(PROP_HEIGHT, Property::new(Prop::Length(None), "Height of the region.").required().min(0.0));
         map
     };
 }
 
 /// Image crop processor.
 pub struct Crop {
-    properties: HashMap<&'static str, Prop>
+    properties: HashMap<&'static str, Property>
 }
 
 impl Crop {
@@ -38,23 +38,46 @@ impl Crop {
 
 impl ImgProc for Crop {
     fn process(&self, img: ImgEdit) -> Result<ImgEdit> {
-        // TODO: Handle errors!
+        // Resolve the crop region against the image dimensions
+        let dimensions = img.dimensions();
+        let (width, height) = dimensions;
+        let resolve = |name: &str, reference: u32| self.property(name)
+            .unwrap()
+            .as_length()
+            .unwrap()
+            .resolve_uint(reference, dimensions)
+            .ok_or(Error::filter("The crop region must not be negative"));
+        let x = resolve(PROP_X, width)?;
+        let y = resolve(PROP_Y, height)?;
+        let crop_width = resolve(PROP_WIDTH, width)?;
+        let crop_height = resolve(PROP_HEIGHT, height)?;
+
+        // Make sure the region isn't empty, and fits inside the image
+        if crop_width == 0 || crop_height == 0 {
+            return Err(Error::filter("The crop region must not be empty"));
+        }
+        if x + crop_width > width || y + crop_height > height {
+            return Err(Error::filter(format!(
+                "The crop region {}x{}+{}+{} does not fit inside the {}x{} image",
+                crop_width, crop_height, x, y, width, height,
+            )));
+        }
+
         Ok(ImgEdit::from(
             img.into_img()
-                .crop(
-                    self.property(PROP_X).unwrap().as_uint().unwrap(),
-                    self.property(PROP_Y).unwrap().as_uint().unwrap(),
-                    self.property(PROP_WIDTH).unwrap().as_uint().unwrap(),
-                    self.property(PROP_HEIGHT).unwrap().as_uint().unwrap(),
-                )
+                .crop(x, y, crop_width, crop_height)
         ))
     }
 
-    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Prop> {
+    fn keeps_size(&self) -> bool {
+        false
+    }
+
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
         &self.properties
     }
 
-    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Prop> {
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
         &mut self.properties
     }
 }
\ No newline at end of file
@@ -3,16 +3,16 @@ use std::collections::HashMap;
 use err::Result;
 use img::ImgEdit;
 
-use super::{ImgProc, Prop};
+use super::{ImgProc, Property};
 
 // Default properties
 lazy_static! {
-    static ref PROPERTIES: HashMap<&'static str, Prop> = HashMap::new();
+    static ref PROPERTIES: HashMap<&'static str, Property> = HashMap::new();
 }
 
 /// Image vertical flip processor.
 pub struct FlipV {
-    properties: HashMap<&'static str, Prop>
+    properties: HashMap<&'static str, Property>
 }
 
 impl FlipV {
@@ -30,11 +30,11 @@ impl ImgProc for FlipV {
         ))
     }
 
-    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Prop> {
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
         &self.properties
     }
 
-    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Prop> {
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
         &mut self.properties
     }
 }
\ No newline at end of file
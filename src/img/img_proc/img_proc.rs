@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use img::ImgEdit;
 use err::{Error, Result};
 
-use super::Prop;
+use super::{Prop, Property};
 
 /// Image processor trait.
 pub trait ImgProc {
@@ -11,11 +11,15 @@ pub trait ImgProc {
     ///
     /// The given image is consumed, a new and edited image is returned on success.
     ///
-    /// This method checks whether all parameters are filled in before processing.
+    /// This method checks whether all required properties are filled in before processing.
     /// An error may be returned if processing failed, or if the given parameters were incorrect.
     fn process_safe(&self, img: ImgEdit) -> Result<ImgEdit> {
-        if !self.empty_properties().is_empty() {
-            Err(Error::new("Unable to start image processor, missing properties"))
+        let missing = self.missing_properties();
+        if !missing.is_empty() {
+            Err(Error::filter(format!(
+                "Unable to start image processor, missing properties: {}",
+                missing.join(", "),
+            )))
         } else {
             self.process(img)
         }
@@ -28,39 +32,44 @@ pub trait ImgProc {
     /// An error may be returned if processing failed, or if the given parameters were incorrect.
     fn process(&self, img: ImgEdit) -> Result<ImgEdit>;
 
-    /// List of properties.
-    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Prop>;
+    /// Check whether this processor keeps the size of the image it processes.
+    ///
+    /// Processors that change the size, such as crop and resize,
+    /// can't be applied to the part of the image of each monitor separately.
+    fn keeps_size(&self) -> bool {
+        true
+    }
+
+    /// List of properties, with their metadata.
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property>;
 
-    /// List of mutable properties.
-    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Prop>;
+    /// List of mutable properties, with their metadata.
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property>;
 
-    /// Get a list of empty properties.
-    fn empty_properties(&self) -> Vec<&'static str> {
-        self.properties()
+    /// Get a sorted list of required properties that don't have a value.
+    fn missing_properties(&self) -> Vec<&'static str> {
+        let mut missing: Vec<&'static str> = self.properties()
             .iter()
-            .filter(|property| property.1.is_empty())
+            .filter(|property| property.1.is_missing())
             .map(|property| *property.0)
-            .collect()
+            .collect();
+        missing.sort();
+        missing
     }
 
-    /// Get a reference to a property by `name`.
+    /// Get a reference to a property value by `name`.
     fn property<'a: 'b, 'b>(&'a self, name: &'b str) -> Option<&'b Prop> {
-        self.properties().get(name)
-    }
-
-    /// Get a mutable reference to a property by `name`.
-    fn mut_property<'a: 'b, 'b>(&'a mut self, name: &'b str) -> Option<&'b mut Prop> {
-        self.mut_properties().get_mut(name)
+        self.properties().get(name).map(|property| &property.prop)
     }
 
     /// Set a property by the given `name` to the given string `value`.
     ///
-    /// Returns an error if the property wasn't found,
-    /// or it the value couldn't be parsed because it was incompatible.
-    fn set_property<'a>(&mut self, name: &str, value: &str) -> Result<'a, ()> {
-        match self.mut_property(name) {
-            Some(ref mut prop) => prop.parse(value.as_ref()),
-            None => Err(Error::new("Tried to set unknown option on a filter")),
+    /// Returns an error if the property wasn't found, if the value couldn't be parsed because
+    /// it was incompatible, or if it is outside the bounds of the property.
+    fn set_property(&mut self, name: &str, value: &str) -> Result<()> {
+        match self.mut_properties().get_mut(name) {
+            Some(property) => property.parse(name, value),
+            None => Err(Error::filter(format!("Tried to set unknown property '{}' on a filter", name))),
         }
     }
 }
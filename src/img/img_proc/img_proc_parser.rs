@@ -6,6 +6,30 @@ use self::regex::Regex;
 
 use err::{Error, Result};
 
+/// All available filters by name, with a function to create a new instance.
+///
+/// This list is kept in alphabetical order.
+static FILTERS: &'static [(&'static str, fn() -> Box<ImgProc>)] = &[
+    ("blur", || Box::new(Blur::new())),
+    ("brighten", || Box::new(Brighten::new())),
+    ("contrast", || Box::new(Contrast::new())),
+    ("crop", || Box::new(Crop::new())),
+    ("fliph", || Box::new(FlipH::new())),
+    ("flipv", || Box::new(FlipV::new())),
+    ("greyscale", || Box::new(Greyscale::new())),
+    ("huerotate", || Box::new(HueRotate::new())),
+    ("invert", || Box::new(Invert::new())),
+    ("overlay", || Box::new(Overlay::new())),
+    ("pixelate", || Box::new(Pixelate::new())),
+    ("resize", || Box::new(Resize::new())),
+    ("rotate90", || Box::new(Rotate90::new())),
+    ("rotate180", || Box::new(Rotate180::new())),
+    ("rotate270", || Box::new(Rotate270::new())),
+    ("text", || Box::new(Text::new())),
+    ("unsharpen", || Box::new(Unsharpen::new())),
+    ("vignette", || Box::new(Vignette::new())),
+];
+
 /// Image processor parser.
 ///
 /// This parses an image processor with options from a given string.
@@ -16,29 +40,33 @@ impl ImgProcParser {
 
     /// Parse the filter form the given `filter` string.
     ///
-    /// An error is returned if parsing failed.
-    pub fn parse<'a: 'b, 'b>(filter: &'b str) -> Result<'a, Box<ImgProc>> {
+    /// An error is returned if parsing failed, which includes the filter string.
+    pub fn parse(filter: &str) -> Result<Box<ImgProc>> {
+        ImgProcParser::parse_inner(filter)
+            .map_err(|err| Error::filter(format!("Failed to parse filter '{}'", filter.trim())).caused_by(err))
+    }
+
+    /// Parse the filter from the given `filter` string, see `parse`.
+    fn parse_inner(filter: &str) -> Result<Box<ImgProc>> {
         // Define regular expressions for the full filter syntax, and filter parameters
-        let re_filter = Regex::new(r"^\s*([a-zA-Z]+)\s*(:[a-zA-Z0-9=;,\.\-\s]*)?$").unwrap();
-        let re_prop = Regex::new(r"^\s*([a-zA-Z]+)\s*=\s*([a-zA-Z0-9\.\-]*)\s*$").unwrap();
+        let re_filter = Regex::new(r"^\s*([a-zA-Z0-9]+)\s*(:.*)?$").unwrap();
+        let re_prop = Regex::new(r#"^\s*([a-zA-Z]+)\s*=\s*("[^"]*"|'[^']*'|[^"';]*?)\s*$"#).unwrap();
 
         // Get the filter components, skip the first super match
         let filter_matches = re_filter
             .captures_iter(filter)
             .next()
-            .ok_or(Error::new("Incorrect filter format"))?;
+            .ok_or(Error::filter("Incorrect filter format"))?;
         let mut filter_components = filter_matches.iter();
         filter_components.next();
 
         // Determine the filter name
         let filter_name: &str = filter_components
             .next()
-            .ok_or(Error::new("Unable to find filter name, maybe it is missing"))?
-            .ok_or(Error::new("Failed to parse the filter name"))?
+            .ok_or(Error::filter("Unable to find filter name, maybe it is missing"))?
+            .ok_or(Error::filter("Failed to parse the filter name"))?
             .as_str();
 
-        println!("Filter name: {:?}", filter_name);
-
         // Create a list of property strings
         let mut property_strings: Vec<(&str, &str)> = vec![];
 
@@ -47,31 +75,37 @@ impl ImgProcParser {
             // Get the component properties, strip the colon
             let component_properties = &component_properties.as_str()[1..];
 
-            // Split the properties by the comma
-            for component_property in component_properties.split(';') {
+            // Split the properties by the semicolon
+            for component_property in ImgProcParser::split_properties(component_properties) {
                 // Get the property components, skip the first super match
                 let property_matches = re_prop
                     .captures_iter(component_property)
                     .next()
-                    .ok_or(Error::new("Incorrect filter property format"))?;
+                    .ok_or(Error::filter("Incorrect filter property format"))?;
                 let mut property_components = property_matches.iter();
                 property_components.next();
 
                 // Get the property name
                 let prop_name = property_components
                     .next()
-                    .ok_or(Error::new("Missing filter property name"))?
-                    .ok_or(Error::new("Unable to parse filter property name"))?
+                    .ok_or(Error::filter("Missing filter property name"))?
+                    .ok_or(Error::filter("Unable to parse filter property name"))?
                     .as_str();
 
                 // Get the property value
                 let prop_val = property_components
                     .next()
-                    .ok_or(Error::new("Missing filter property value"))?
-                    .ok_or(Error::new("Unable to parse filter property value"))?
+                    .ok_or(Error::filter("Missing filter property value"))?
+                    .ok_or(Error::filter("Unable to parse filter property value"))?
                     .as_str();
 
-                println!("Prop: {:?}, value: {:?}", prop_name, prop_val);
+                // Strip the quotes from quoted values
+                let prop_val = if prop_val.len() >= 2
+                    && (prop_val.starts_with('"') || prop_val.starts_with('\'')) {
+                    &prop_val[1..prop_val.len() - 1]
+                } else {
+                    prop_val
+                };
 
                 // Add the property name and value to the property strings list
                 property_strings.push((prop_name, prop_val));
@@ -82,13 +116,39 @@ impl ImgProcParser {
         ImgProcParser::parse_parts(filter_name, property_strings)
     }
 
+    /// Split the given filter `properties` string by semicolons into separate property strings.
+    ///
+    /// Semicolons inside single or double quoted values don't split the string.
+    fn split_properties(properties: &str) -> Vec<&str> {
+        let mut parts = vec![];
+        let mut start = 0;
+        let mut quote: Option<char> = None;
+
+        for (i, c) in properties.char_indices() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {},
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None if c == ';' => {
+                    parts.push(&properties[start..i]);
+                    start = i + 1;
+                },
+                None => {},
+            }
+        }
+        parts.push(&properties[start..]);
+
+        parts
+    }
+
     /// Parse the filter with the given `name`, and set the given `properties` on it.
     ///
     /// If the filter name is unknown, an error is returned.
     ///
     /// The properties are in `key`-`value` tuple format.
-    /// Properties that are not known for the filter return an error.
-    pub fn parse_parts<'a>(name: &str, properties: Vec<(&str, &str)>) -> Result<'a, Box<ImgProc>> {
+    /// Properties that are not known for the filter, values that are invalid
+    /// and required properties that are missing return an error.
+    pub fn parse_parts(name: &str, properties: Vec<(&str, &str)>) -> Result<Box<ImgProc>> {
         // Parse the filter by it's name
         let mut filter = ImgProcParser::create_filter_by_name(name)?;
 
@@ -97,6 +157,16 @@ impl ImgProcParser {
             filter.set_property(key, value)?;
         }
 
+        // Make sure all required properties are set
+        let missing = filter.missing_properties();
+        if !missing.is_empty() {
+            return Err(Error::filter(format!(
+                "Missing required properties for filter '{}': {}",
+                name.trim(),
+                missing.join(", "),
+            )));
+        }
+
         Ok(filter)
     }
 
@@ -105,23 +175,19 @@ impl ImgProcParser {
     /// If the name `blur` is given, a new instance of the image blur processor is returned.
     ///
     /// An error is returned if the filter name is unknown.
-    pub fn create_filter_by_name<'a: 'b, 'b>(name: &'b str) -> Result<'a, Box<ImgProc>> {
-        match name.trim().to_lowercase().as_str() {
-            "blur" => Ok(Box::new(Blur::new())),
-            "brighten" => Ok(Box::new(Brighten::new())),
-            "contrast" => Ok(Box::new(Contrast::new())),
-            "crop" => Ok(Box::new(Crop::new())),
-            "fliph" => Ok(Box::new(FlipH::new())),
-            "flipv" => Ok(Box::new(FlipV::new())),
-            "greyscale" => Ok(Box::new(Greyscale::new())),
-            "huerotate" => Ok(Box::new(HueRotate::new())),
-            "invert" => Ok(Box::new(Invert::new())),
-            "resize" => Ok(Box::new(Resize::new())),
-            "rotate90" => Ok(Box::new(Rotate90::new())),
-            "rotate180" => Ok(Box::new(Rotate180::new())),
-            "rotate270" => Ok(Box::new(Rotate270::new())),
-            "unsharpen" => Ok(Box::new(Unsharpen::new())),
-            _ => Err(Error::new("Unknown filter name")),
-        }
+    pub fn create_filter_by_name(name: &str) -> Result<Box<ImgProc>> {
+        let name = name.trim().to_lowercase();
+
+        FILTERS.iter()
+            .find(|filter| filter.0 == name)
+            .map(|filter| filter.1())
+            .ok_or(Error::filter("Unknown filter name"))
+    }
+
+    /// Get the names of all available filters, in alphabetical order.
+    pub fn filter_names() -> Vec<&'static str> {
+        FILTERS.iter()
+            .map(|filter| filter.0)
+            .collect()
     }
-}
\ No newline at end of file
+}
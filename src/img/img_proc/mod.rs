@@ -9,12 +9,17 @@ pub mod huerotate;
 pub mod img_proc;
 pub mod img_proc_parser;
 pub mod invert;
+pub mod overlay;
+pub mod pixelate;
 pub mod prop;
+pub mod property;
 pub mod resize;
 pub mod rotate90;
 pub mod rotate180;
 pub mod rotate270;
+pub mod text;
 pub mod unsharpen;
+pub mod vignette;
 
 // Reexport modules
 pub use self::blur::Blur;
@@ -28,9 +33,14 @@ pub use self::huerotate::HueRotate;
 pub use self::img_proc::ImgProc;
 pub use self::img_proc_parser::ImgProcParser;
 pub use self::invert::Invert;
+pub use self::overlay::Overlay;
+pub use self::pixelate::Pixelate;
 pub use self::prop::Prop;
+pub use self::property::Property;
 pub use self::resize::Resize;
 pub use self::rotate90::Rotate90;
 pub use self::rotate180::Rotate180;
 pub use self::rotate270::Rotate270;
+pub use self::text::Text;
 pub use self::unsharpen::Unsharpen;
+pub use self::vignette::Vignette;
@@ -0,0 +1,171 @@
+extern crate image;
+extern crate shellexpand;
+
+use std::collections::HashMap;
+
+use self::image::{DynamicImage, FilterType, GenericImage, RgbaImage};
+
+use err::{Error, Result};
+use img::{Gravity, ImgEdit, Length};
+use img::color::blend;
+use img::gravity::GRAVITIES;
+
+use super::{ImgProc, Prop, Property};
+
+// Property names
+pub static PROP_PATH: &'static str = "path";
+pub static PROP_GRAVITY: &'static str = "gravity";
+pub static PROP_X: &'static str = "x";
+pub static PROP_Y: &'static str = "y";
+pub static PROP_OPACITY: &'static str = "opacity";
+pub static PROP_SCALE: &'static str = "scale";
+
+// Default properties
+lazy_static! {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
+        let mut map = HashMap::new();
+        map.insert(PROP_PATH, Property::new(Prop::String(None), "Path of the image to draw.").required());
+        map.insert(PROP_GRAVITY, Property::new(Prop::Enum(Some("center".into()), GRAVITIES), "Where to place the overlay."));
+        map.insert(PROP_X, Property::new(Prop::Length(Some(Length::Px(0.0))), "Horizontal offset from the edge."));
+        map.insert(PROP_Y, Property::new(Prop::Length(Some(Length::Px(0.0))), "Vertical offset from the edge."));
+        map.insert(PROP_OPACITY, Property::new(Prop::Float(Some(1.0)), "Opacity of the overlay.")
+            .min(0.0)
+            .max(1.0));
+        map.insert(PROP_SCALE, Property::new(Prop::Float(Some(1.0)), "Factor to scale the overlay image by.").min(0.01));
+        map
+    };
+}
+
+/// Image overlay processor.
+///
+/// This composites another image, such as a padlock icon or logo, on top of the image.
+pub struct Overlay {
+    properties: HashMap<&'static str, Property>
+}
+
+impl Overlay {
+    pub fn new() -> Overlay {
+        Overlay {
+            properties: PROPERTIES.clone()
+        }
+    }
+
+    /// Load the overlay image from the configured path, and scale it if required.
+    fn load_overlay(&self) -> Result<RgbaImage> {
+        // Expand the path and load the image
+        let path = self.property(PROP_PATH).unwrap().as_str().unwrap();
+        let path = shellexpand::tilde(&path).to_string();
+        let overlay = image::open(&path)
+            .map_err(|err| Error::filter(format!("Failed to load overlay image {:?}", path)).caused_by(err))?;
+
+        // Scale the overlay
+        let scale = self.property(PROP_SCALE).unwrap().as_float().unwrap();
+        if scale == 1f32 {
+            return Ok(overlay.to_rgba());
+        }
+
+        let (width, height) = overlay.dimensions();
+        Ok(
+            overlay.resize_exact(
+                ((width as f32 * scale).round() as u32).max(1),
+                ((height as f32 * scale).round() as u32).max(1),
+                FilterType::Triangle,
+            ).to_rgba()
+        )
+    }
+}
+
+impl ImgProc for Overlay {
+    fn process(&self, img: ImgEdit) -> Result<ImgEdit> {
+        // Get the opacity and gravity to use
+        let opacity = self.property(PROP_OPACITY).unwrap().as_float().unwrap();
+        let gravity = Gravity::parse(&self.property(PROP_GRAVITY).unwrap().as_str().unwrap())?;
+
+        // Load the overlay, and get the image to draw on
+        let overlay = self.load_overlay()?;
+        let mut buf = img.into_img().to_rgba();
+        let (width, height) = buf.dimensions();
+
+        // Determine where to place the overlay
+        let (left, top) = gravity.position(
+            buf.dimensions(),
+            overlay.dimensions(),
+            (
+                self.property(PROP_X).unwrap().as_length().unwrap().resolve(width, (width, height)),
+                self.property(PROP_Y).unwrap().as_length().unwrap().resolve(height, (width, height)),
+            ),
+        );
+
+        // Blend each overlay pixel that is inside the image
+        for (x, y, pixel) in overlay.enumerate_pixels() {
+            let (target_x, target_y) = (left + x as i64, top + y as i64);
+            if target_x < 0 || target_y < 0 || target_x >= width as i64 || target_y >= height as i64 {
+                continue;
+            }
+
+            let target = buf.get_pixel_mut(target_x as u32, target_y as u32);
+            *target = blend(target, pixel, opacity);
+        }
+
+        Ok(ImgEdit::from(DynamicImage::ImageRgba8(buf)))
+    }
+
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
+        &self.properties
+    }
+
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
+        &mut self.properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::image::{DynamicImage, GenericImage, Rgba, RgbaImage};
+    use tempdir::TempDir;
+
+    use img::ImgEdit;
+    use img::img_proc::ImgProc;
+
+    use super::{Overlay, PROP_GRAVITY, PROP_OPACITY, PROP_PATH, PROP_X};
+
+    /// Draw a 2x2 white overlay on a 6x4 black image, with the given properties.
+    fn overlay(props: &[(&str, &str)]) -> DynamicImage {
+        let dir = TempDir::new("overlay").unwrap();
+        let path = dir.path().join("overlay.png");
+        RgbaImage::from_pixel(2, 2, Rgba([255, 255, 255, 255])).save(&path).unwrap();
+
+        let mut overlay = Overlay::new();
+        overlay.set_property(PROP_PATH, path.to_str().unwrap()).unwrap();
+        for &(name, value) in props {
+            overlay.set_property(name, value).unwrap();
+        }
+        let img = RgbaImage::from_pixel(6, 4, Rgba([0, 0, 0, 255]));
+        overlay.process_safe(ImgEdit::from(DynamicImage::ImageRgba8(img))).unwrap().into_img()
+    }
+
+    #[test]
+    fn center() {
+        let img = overlay(&[]);
+        assert_eq!(img.dimensions(), (6, 4));
+        assert_eq!(img.get_pixel(2, 1), Rgba([255, 255, 255, 255]));
+        assert_eq!(img.get_pixel(3, 2), Rgba([255, 255, 255, 255]));
+        assert_eq!(img.get_pixel(1, 1), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn gravity_offset_opacity() {
+        let img = overlay(&[(PROP_GRAVITY, "top-right"), (PROP_X, "1"), (PROP_OPACITY, "0.5")]);
+        assert_eq!(img.dimensions(), (6, 4));
+        assert_eq!(img.get_pixel(3, 0), Rgba([128, 128, 128, 255]));
+        assert_eq!(img.get_pixel(5, 0), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn missing_image() {
+        let mut overlay = Overlay::new();
+        overlay.set_property(PROP_PATH, "/nonexistent/overlay.png").unwrap();
+        let img = RgbaImage::from_pixel(6, 4, Rgba([0, 0, 0, 255]));
+        assert!(overlay.process_safe(ImgEdit::from(DynamicImage::ImageRgba8(img))).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+extern crate image;
+
+use std::cmp;
+use std::collections::HashMap;
+
+use self::image::{DynamicImage, Rgba, RgbaImage};
+
+use err::Result;
+use img::ImgEdit;
+
+use super::{ImgProc, Prop, Property};
+
+// Property names
+pub static PROP_SIZE: &'static str = "size";
+pub static PROP_MODE: &'static str = "mode";
+
+pub const MODE_AVERAGE: &'static str = "average";
+pub const MODE_SAMPLE: &'static str = "sample";
+
+/// Allowed values for the mode property.
+static MODES: &'static [&'static str] = &[MODE_AVERAGE, MODE_SAMPLE];
+
+// Default properties
+lazy_static! {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
+        let mut map = HashMap::new();
+        map.insert(PROP_SIZE, Property::new(Prop::UInt(Some(16)), "Block size in pixels.").min(1.0));
+        map.insert(PROP_MODE, Property::new(Prop::Enum(Some(MODE_AVERAGE.into()), MODES), "How to determine the color of each block."));
+        map
+    };
+}
+
+/// Image pixelate processor.
+pub struct Pixelate {
+    properties: HashMap<&'static str, Property>
+}
+
+impl Pixelate {
+    pub fn new() -> Pixelate {
+        Pixelate {
+            properties: PROPERTIES.clone()
+        }
+    }
+
+    /// Compute the color of the block at the given `x` and `y` with the given dimensions,
+    /// by averaging all pixels in the block.
+    fn block_average(img: &RgbaImage, x: u32, y: u32, width: u32, height: u32) -> Rgba<u8> {
+        // Sum all channels of the pixels in this block
+        let mut sum = [0u64; 4];
+        for py in y..y + height {
+            for px in x..x + width {
+                let pixel = img.get_pixel(px, py);
+                for (channel, value) in sum.iter_mut().zip(pixel.data.iter()) {
+                    *channel += *value as u64;
+                }
+            }
+        }
+
+        // Divide each channel by the number of pixels
+        let count = (width * height) as u64;
+        Rgba([
+            (sum[0] / count) as u8,
+            (sum[1] / count) as u8,
+            (sum[2] / count) as u8,
+            (sum[3] / count) as u8,
+        ])
+    }
+
+    /// Get the color of the block at the given `x` and `y` with the given dimensions,
+    /// by sampling the pixel in the center of the block.
+    fn block_sample(img: &RgbaImage, x: u32, y: u32, width: u32, height: u32) -> Rgba<u8> {
+        *img.get_pixel(x + width / 2, y + height / 2)
+    }
+}
+
+impl ImgProc for Pixelate {
+    fn process(&self, img: ImgEdit) -> Result<ImgEdit> {
+        // Get the block size
+        let size = self.property(PROP_SIZE).unwrap().as_uint().unwrap();
+
+        // Determine what method to use for picking the block color,
+        // the mode property only allows known modes
+        let mode = self.property(PROP_MODE).unwrap().as_str().unwrap();
+        let block_color: fn(&RgbaImage, u32, u32, u32, u32) -> Rgba<u8> = match mode.as_str() {
+            MODE_SAMPLE => Pixelate::block_sample,
+            _ => Pixelate::block_average,
+        };
+
+        // Get the image as RGBA buffer
+        let mut buf = img.into_img().to_rgba();
+        let (width, height) = buf.dimensions();
+
+        // Fill each block with a single color
+        for y in (0..height).step_by(size as usize) {
+            let block_height = cmp::min(size, height - y);
+
+            for x in (0..width).step_by(size as usize) {
+                let block_width = cmp::min(size, width - x);
+
+                let color = block_color(&buf, x, y, block_width, block_height);
+                for py in y..y + block_height {
+                    for px in x..x + block_width {
+                        buf.put_pixel(px, py, color);
+                    }
+                }
+            }
+        }
+
+        Ok(ImgEdit::from(DynamicImage::ImageRgba8(buf)))
+    }
+
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
+        &self.properties
+    }
+
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
+        &mut self.properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::image::{DynamicImage, GenericImage, Rgba, RgbaImage};
+
+    use img::ImgEdit;
+    use img::img_proc::ImgProc;
+
+    use super::{Pixelate, MODE_SAMPLE, PROP_MODE, PROP_SIZE};
+
+    /// Pixelate a 5x3 image, where each pixel has a distinct red value, with the given `mode`.
+    fn pixelate(mode: &str) -> DynamicImage {
+        let img = RgbaImage::from_fn(5, 3, |x, y| Rgba([(y * 5 + x) as u8 * 10, 0, 0, 255]));
+        let mut pixelate = Pixelate::new();
+        pixelate.set_property(PROP_SIZE, "2").unwrap();
+        pixelate.set_property(PROP_MODE, mode).unwrap();
+        pixelate.process_safe(ImgEdit::from(DynamicImage::ImageRgba8(img))).unwrap().into_img()
+    }
+
+    #[test]
+    fn average() {
+        let img = pixelate("average");
+        assert_eq!(img.dimensions(), (5, 3));
+
+        // The top left block averages 0, 10, 50 and 60
+        assert_eq!(img.get_pixel(0, 0), Rgba([30, 0, 0, 255]));
+        assert_eq!(img.get_pixel(1, 1), Rgba([30, 0, 0, 255]));
+
+        // The partial block in the bottom right corner is a single pixel
+        assert_eq!(img.get_pixel(4, 2), Rgba([140, 0, 0, 255]));
+    }
+
+    #[test]
+    fn sample() {
+        let img = pixelate(MODE_SAMPLE);
+        assert_eq!(img.dimensions(), (5, 3));
+
+        // Blocks take the color of their center pixel
+        assert_eq!(img.get_pixel(0, 0), Rgba([60, 0, 0, 255]));
+        assert_eq!(img.get_pixel(3, 0), Rgba([80, 0, 0, 255]));
+        assert_eq!(img.get_pixel(2, 2), Rgba([130, 0, 0, 255]));
+    }
+}
@@ -1,4 +1,5 @@
-use err::Result;
+use err::{Error, Result};
+use img::{Color, Length};
 
 #[derive(Clone)]
 pub enum Prop {
@@ -16,6 +17,15 @@ pub enum Prop {
 
     /// A string value.
     String(Option<String>),
+
+    /// A color value.
+    Color(Option<Color>),
+
+    /// A length value, in pixels or relative to the image.
+    Length(Option<Length>),
+
+    /// A string value, that must be one of the given allowed values.
+    Enum(Option<String>, &'static [&'static str]),
 }
 
 impl Prop {
@@ -23,7 +33,7 @@ impl Prop {
     ///
     /// If parsing fails or if the given `value` is not compatible with the type,
     /// an error is returned.
-    pub fn parse<'a>(&mut self, value: &str) -> Result<'a, ()> {
+    pub fn parse(&mut self, value: &str) -> Result<()> {
         // Parse and update the type
         match *self {
             Prop::Int(ref mut x) => {
@@ -44,6 +54,24 @@ impl Prop {
             Prop::String(ref mut x) => {
                 *x = Some(value.to_string());
             },
+            Prop::Color(ref mut x) => {
+                *x = Some(Color::parse(value)?);
+            },
+            Prop::Length(ref mut x) => {
+                *x = Some(Length::parse(value)?);
+            },
+            Prop::Enum(ref mut x, values) => {
+                // Normalize the value, and make sure it is allowed
+                let value = value.trim().to_lowercase();
+                if !values.contains(&value.as_str()) {
+                    return Err(Error::filter(format!(
+                        "Invalid value '{}', use one of: {}",
+                        value,
+                        values.join(", "),
+                    )));
+                }
+                *x = Some(value);
+            },
         }
 
         Ok(())
@@ -57,6 +85,31 @@ impl Prop {
             Prop::Float(x) => x.is_none(),
             Prop::Bool(x) => x.is_none(),
             Prop::String(ref x) => x.clone().map(|x| x.is_empty()).unwrap_or(true),
+            Prop::Color(x) => x.is_none(),
+            Prop::Length(x) => x.is_none(),
+            Prop::Enum(ref x, _) => x.is_none(),
+        }
+    }
+
+    /// Get the name of the type of this property, such as `int` or `string`.
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Prop::Int(_) => "int",
+            Prop::UInt(_) => "uint",
+            Prop::Float(_) => "float",
+            Prop::Bool(_) => "bool",
+            Prop::String(_) => "string",
+            Prop::Color(_) => "color",
+            Prop::Length(_) => "length",
+            Prop::Enum(..) => "enum",
+        }
+    }
+
+    /// Get the allowed values of this property, if it is an enum.
+    pub fn choices(&self) -> Option<&'static [&'static str]> {
+        match *self {
+            Prop::Enum(_, values) => Some(values),
+            _ => None,
         }
     }
 
@@ -70,6 +123,7 @@ impl Prop {
             Prop::String(ref x) => x.clone()
                 .map(|x| x.parse::<i32>().ok())
                 .unwrap_or(None),
+            Prop::Color(_) | Prop::Length(_) | Prop::Enum(..) => None,
         }
     }
 
@@ -83,6 +137,7 @@ impl Prop {
             Prop::String(ref x) => x.clone()
                 .map(|x| x.parse::<u32>().ok())
                 .unwrap_or(None),
+            Prop::Color(_) | Prop::Length(_) | Prop::Enum(..) => None,
         }
     }
 
@@ -96,6 +151,21 @@ impl Prop {
             Prop::String(ref x) => x.clone()
                 .map(|x| x.parse::<f32>().ok())
                 .unwrap_or(None),
+            Prop::Color(_) | Prop::Length(_) | Prop::Enum(..) => None,
+        }
+    }
+
+    /// Get the numeric value of the property, used for checking it's bounds.
+    ///
+    /// For lengths this is the number without it's unit.
+    /// If the property isn't numeric or is empty, `None` is returned.
+    pub fn as_number(&self) -> Option<f32> {
+        match *self {
+            Prop::Int(x) => x.map(|x| x as f32),
+            Prop::UInt(x) => x.map(|x| x as f32),
+            Prop::Float(x) => x,
+            Prop::Length(x) => x.map(|x| x.value()),
+            _ => None,
         }
     }
 
@@ -111,6 +181,7 @@ impl Prop {
             Prop::String(ref x) => x.clone()
                 .map(|x| x.parse::<bool>().ok())
                 .unwrap_or(None),
+            Prop::Color(_) | Prop::Length(_) | Prop::Enum(..) => None,
         }
 
     }
@@ -123,6 +194,34 @@ impl Prop {
             Prop::Float(x) => x.map(|x| x.to_string()),
             Prop::Bool(x) => x.map(|x| if x { "true" } else { "false" }).map(|x| x.into()),
             Prop::String(ref x) => x.clone(),
+            Prop::Color(x) => x.map(|x| x.to_hex()),
+            Prop::Length(x) => x.map(|x| x.to_string()),
+            Prop::Enum(ref x, _) => x.clone(),
+        }
+    }
+
+    /// Get the property as color.
+    pub fn as_color(&self) -> Option<Color> {
+        match *self {
+            Prop::Color(x) => x,
+            Prop::String(ref x) => x.clone()
+                .map(|x| Color::parse(&x).ok())
+                .unwrap_or(None),
+            _ => None,
+        }
+    }
+
+    /// Get the property as length.
+    pub fn as_length(&self) -> Option<Length> {
+        match *self {
+            Prop::Length(x) => x,
+            Prop::Int(x) => x.map(|x| Length::Px(x as f32)),
+            Prop::UInt(x) => x.map(|x| Length::Px(x as f32)),
+            Prop::Float(x) => x.map(Length::Px),
+            Prop::String(ref x) => x.clone()
+                .map(|x| Length::parse(&x).ok())
+                .unwrap_or(None),
+            _ => None,
         }
     }
 }
\ No newline at end of file
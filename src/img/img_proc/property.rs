@@ -0,0 +1,177 @@
+use err::{Error, Result};
+
+use super::Prop;
+
+/// A filter property, holding it's value and metadata describing which values are accepted.
+///
+/// The metadata is used to validate values when they are set, and to generate help output.
+#[derive(Clone)]
+pub struct Property {
+    /// The property value, which defines it's type.
+    pub prop: Prop,
+
+    /// A short description of the property.
+    pub description: &'static str,
+
+    /// Whether a value must be given for the property.
+    pub required: bool,
+
+    /// The minimum numeric value, inclusive.
+    pub min: Option<f32>,
+
+    /// The maximum numeric value, inclusive.
+    pub max: Option<f32>,
+}
+
+impl Property {
+    /// Construct a new optional property with the given default `prop` and `description`.
+    pub fn new(prop: Prop, description: &'static str) -> Self {
+        Property {
+            prop,
+            description,
+            required: false,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Mark the property as required.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set the minimum numeric value.
+    pub fn min(mut self, min: f32) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Set the maximum numeric value.
+    pub fn max(mut self, max: f32) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Check whether the property is required but doesn't have a value.
+    pub fn is_missing(&self) -> bool {
+        self.required && self.prop.is_empty()
+    }
+
+    /// Parse the given `value` for the property with the given `name`, and check it's bounds.
+    ///
+    /// The property is only updated if the value is valid, an error is returned otherwise.
+    pub fn parse(&mut self, name: &str, value: &str) -> Result<()> {
+        // Parse the value into a copy
+        let mut prop = self.prop.clone();
+        prop.parse(value).map_err(|err| Error::filter(
+            format!("Invalid value for property '{}'", name)
+        ).caused_by(err))?;
+
+        // Check the bounds of numeric values, which must be finite
+        if let Some(number) = prop.as_number() {
+            if !number.is_finite() {
+                return Err(Error::filter(format!(
+                    "Invalid value for property '{}': {} is not a finite number",
+                    name,
+                    value.trim(),
+                )));
+            }
+
+            let below = self.min.map(|min| number < min).unwrap_or(false);
+            let above = self.max.map(|max| number > max).unwrap_or(false);
+            if below || above {
+                return Err(Error::filter(format!(
+                    "Invalid value for property '{}': {} is out of range, must be {}",
+                    name,
+                    value.trim(),
+                    self.bounds().unwrap(),
+                )));
+            }
+        }
+
+        self.prop = prop;
+        Ok(())
+    }
+
+    /// Describe the numeric bounds of the property, such as `from 0 to 1` or `at least 0`.
+    ///
+    /// If the property is unbounded, `None` is returned.
+    pub fn bounds(&self) -> Option<String> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => Some(format!("from {} to {}", min, max)),
+            (Some(min), None) => Some(format!("at least {}", min)),
+            (None, Some(max)) => Some(format!("at most {}", max)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use img::Length;
+    use super::Property;
+    use super::super::Prop;
+
+    /// A float property from 0 to 10, without a value.
+    fn bounded() -> Property {
+        Property::new(Prop::Float(None), "Test.").min(0.0).max(10.0)
+    }
+
+    #[test]
+    fn within_bounds() {
+        let mut property = bounded();
+        assert!(property.parse("test", "0").is_ok());
+        assert!(property.parse("test", "10").is_ok());
+        assert!(property.parse("test", "2.5").is_ok());
+        assert_eq!(property.prop.as_float(), Some(2.5));
+    }
+
+    #[test]
+    fn below_min() {
+        let mut property = bounded();
+        property.parse("test", "5").unwrap();
+        let err = property.parse("test", "-0.5").unwrap_err();
+        assert!(err.to_string().contains("-0.5 is out of range, must be from 0 to 10"));
+        assert_eq!(property.prop.as_float(), Some(5.0));
+    }
+
+    #[test]
+    fn above_max() {
+        let mut property = bounded();
+        let err = property.parse("test", "10.5").unwrap_err();
+        assert!(err.to_string().contains("10.5 is out of range, must be from 0 to 10"));
+        assert!(property.prop.is_empty());
+    }
+
+    #[test]
+    fn non_finite() {
+        for value in &["inf", "-inf", "NaN", "infinity"] {
+            let err = bounded().parse("test", value).unwrap_err();
+            assert!(err.to_string().contains("is not a finite number"), "{}", value);
+        }
+
+        // Unbounded numbers and lengths must be finite as well
+        let mut property = Property::new(Prop::Float(None), "Test.");
+        assert!(property.parse("test", "inf").is_err());
+        let mut property = Property::new(Prop::Length(None), "Test.");
+        assert!(property.parse("test", "infpx").is_err());
+        assert!(property.parse("test", "50%").is_ok());
+        match property.prop {
+            Prop::Length(length) => assert_eq!(length, Some(Length::Percent(50.0))),
+            _ => panic!("expected a length"),
+        }
+    }
+
+    #[test]
+    fn missing_required() {
+        let mut property = Property::new(Prop::String(None), "Test.").required();
+        assert!(property.is_missing());
+        property.parse("test", "value").unwrap();
+        assert!(!property.is_missing());
+
+        // Optional properties and required properties with a default are never missing
+        assert!(!Property::new(Prop::String(None), "Test.").is_missing());
+        assert!(!Property::new(Prop::Float(Some(1.0)), "Test.").required().is_missing());
+    }
+}
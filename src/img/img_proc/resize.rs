@@ -7,7 +7,7 @@ use self::image::FilterType;
 use err::{Error, Result};
 use img::ImgEdit;
 
-use super::{ImgProc, Prop};
+use super::{ImgProc, Prop, Property};
 
 // Property names
 pub static PROP_WIDTH: &'static str = "width";
@@ -15,28 +15,33 @@ pub static PROP_HEIGHT: &'static str = "height";
 pub static PROP_RATIO: &'static str = "ratio";
 pub static PROP_FILTER: &'static str = "filter";
 
-pub static FILTER_NEAREST: &'static str = "nearest";
-pub static FILTER_NEAREST_SHORT: &'static str = "near";
-pub static FILTER_TRIANGLE: &'static str = "triangle";
-pub static FILTER_CATMULLROM: &'static str = "catmullrom";
-pub static FILTER_GAUSSIAN: &'static str = "gaussian";
-pub static FILTER_LANCZOS3: &'static str = "lanczos3";
+pub const FILTER_NEAREST: &'static str = "nearest";
+pub const FILTER_NEAREST_SHORT: &'static str = "near";
+pub const FILTER_TRIANGLE: &'static str = "triangle";
+pub const FILTER_CATMULLROM: &'static str = "catmullrom";
+pub const FILTER_GAUSSIAN: &'static str = "gaussian";
+pub const FILTER_LANCZOS3: &'static str = "lanczos3";
+
+/// Allowed values for the filter property.
+static FILTERS: &'static [&'static str] = &[
+    FILTER_NEAREST, FILTER_NEAREST_SHORT, FILTER_TRIANGLE, FILTER_CATMULLROM, FILTER_GAUSSIAN, FILTER_LANCZOS3,
+];
 
 // Default properties
 lazy_static! {
-    static ref PROPERTIES: HashMap<&'static str, Prop> = {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
         let mut map = HashMap::new();
-        map.insert(PROP_WIDTH, Prop::UInt(None));
-        map.insert(PROP_HEIGHT, Prop::UInt(None));
-        map.insert(PROP_RATIO, Prop::Bool(Some(false)));
-        map.insert(PROP_FILTER, Prop::String(Some(FILTER_TRIANGLE.into())));
+        map.insert(PROP_WIDTH, Property::new(Prop::Length(None), "The new width.").required().min(0.0));
+        map.insert(PROP_HEIGHT, Property::new(Prop::Length(None), "The new height.").required().min(0.0));
+        map.insert(PROP_RATIO, Property::new(Prop::Bool(Some(false)), "Keep the aspect ratio, fitting the image in the new dimensions."));
+        map.insert(PROP_FILTER, Property::new(Prop::Enum(Some(FILTER_TRIANGLE.into()), FILTERS), "Image filter to use for resizing."));
         map
     };
 }
 
 /// Image resize processor.
 pub struct Resize {
-    properties: HashMap<&'static str, Prop>
+    properties: HashMap<&'static str, Property>
 }
 
 impl Resize {
@@ -51,7 +56,7 @@ impl Resize {
     /// The filter name is case-insensitive and is trimmed.
     ///
     /// If the filter name was unknown, an error is returned.
-    pub fn parse_filter<'a: 'b, 'b>(filter: &'b str) -> Result<'a, FilterType> {
+    pub fn parse_filter(filter: &str) -> Result<FilterType> {
         // Normalize the filter name
         let filter = filter.trim().to_lowercase();
 
@@ -68,7 +73,7 @@ impl Resize {
             Ok(FilterType::Lanczos3)
         } else {
             // No filter found, return an error
-            Err(Error::new("Unknown filter name."))
+            Err(Error::filter("Unknown filter name."))
         }
     }
 }
@@ -79,9 +84,19 @@ impl ImgProc for Resize {
         let filter_name = self.property(PROP_FILTER).unwrap().as_str().unwrap();
         let filter = Resize::parse_filter(&filter_name)?;
 
-        // Parse the width and height properties
-        let width = self.property(PROP_WIDTH).unwrap().as_uint().unwrap();
-        let height = self.property(PROP_HEIGHT).unwrap().as_uint().unwrap();
+        // Resolve the width and height against the current image dimensions
+        let dimensions = img.dimensions();
+        let resolve = |name: &str, reference: u32| self.property(name)
+            .unwrap()
+            .as_length()
+            .unwrap()
+            .resolve_uint(reference, dimensions)
+            .ok_or(Error::filter("The resize dimensions must not be negative"));
+        let width = resolve(PROP_WIDTH, dimensions.0)?;
+        let height = resolve(PROP_HEIGHT, dimensions.1)?;
+        if width == 0 || height == 0 {
+            return Err(Error::filter("The resize dimensions must be at least 1 pixel"));
+        }
 
         // TODO: Handle errors!
         Ok(ImgEdit::from(
@@ -95,11 +110,15 @@ impl ImgProc for Resize {
         ))
     }
 
-    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Prop> {
+    fn keeps_size(&self) -> bool {
+        false
+    }
+
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
         &self.properties
     }
 
-    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Prop> {
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
         &mut self.properties
     }
 }
\ No newline at end of file
@@ -0,0 +1,438 @@
+extern crate chrono;
+extern crate image;
+extern crate rusttype;
+extern crate shellexpand;
+
+use std::cmp;
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::fmt::Write;
+use std::fs::File;
+use std::io::Read;
+
+use self::chrono::Local;
+use self::image::{DynamicImage, RgbaImage};
+use self::rusttype::{Font, Scale, point};
+
+use err::{Error, Result};
+use img::{Color, Gravity, ImgEdit, Length};
+use img::color::blend;
+use img::gravity::GRAVITIES;
+
+use super::{ImgProc, Prop, Property};
+
+// Property names
+pub static PROP_TEXT: &'static str = "text";
+pub static PROP_FONT: &'static str = "font";
+pub static PROP_SIZE: &'static str = "size";
+pub static PROP_COLOR: &'static str = "color";
+pub static PROP_GRAVITY: &'static str = "gravity";
+pub static PROP_X: &'static str = "x";
+pub static PROP_Y: &'static str = "y";
+pub static PROP_SHADOW: &'static str = "shadow";
+pub static PROP_OUTLINE: &'static str = "outline";
+
+/// The default date format, used for the `{date}` placeholder.
+pub static DATE_FORMAT: &'static str = "%Y-%m-%d %H:%M";
+
+/// The font name that selects the bundled default font.
+pub static FONT_DEFAULT: &'static str = "default";
+
+/// The default font that is bundled with the application.
+static DEFAULT_FONT: &'static [u8] = include_bytes!("../../../res/font/DejaVuSans.ttf");
+
+// Default properties
+lazy_static! {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
+        let mut map = HashMap::new();
+        map.insert(PROP_TEXT, Property::new(Prop::String(None), "Text to draw, may contain placeholders.").required());
+        map.insert(PROP_FONT, Property::new(Prop::String(Some(FONT_DEFAULT.into())), "Path of a TrueType font, or default for the bundled font."));
+        map.insert(PROP_SIZE, Property::new(Prop::Float(Some(32.0)), "Font size in pixels.").min(1.0).max(512.0));
+        map.insert(PROP_COLOR, Property::new(Prop::Color(Some(Color::new(255, 255, 255, 255))), "Text color."));
+        map.insert(PROP_GRAVITY, Property::new(Prop::Enum(Some("center".into()), GRAVITIES), "Where to place the text."));
+        map.insert(PROP_X, Property::new(Prop::Length(Some(Length::Px(0.0))), "Horizontal offset from the edge."));
+        map.insert(PROP_Y, Property::new(Prop::Length(Some(Length::Px(0.0))), "Vertical offset from the edge."));
+        map.insert(PROP_SHADOW, Property::new(Prop::Bool(Some(false)), "Draw a drop shadow below the text."));
+        map.insert(PROP_OUTLINE, Property::new(Prop::UInt(Some(0)), "Width of a dark outline around the text in pixels.").max(64.0));
+        map
+    };
+}
+
+/// Image text processor.
+///
+/// This draws a line of text on top of the image.
+/// Placeholders such as `{hostname}`, `{user}` and `{date:%H:%M}` are expanded when processing.
+pub struct Text {
+    properties: HashMap<&'static str, Property>
+}
+
+/// A coverage mask of rendered text, with values from 0 to 1 for each pixel.
+struct Mask {
+    width: u32,
+    height: u32,
+    data: Vec<f32>,
+}
+
+impl Mask {
+    /// Create an empty mask with the given dimensions.
+    fn new(width: u32, height: u32) -> Self {
+        Mask {
+            width,
+            height,
+            data: vec![0f32; (width * height) as usize],
+        }
+    }
+
+    /// Get the coverage at the given position, zero is returned for positions outside the mask.
+    fn get(&self, x: i64, y: i64) -> f32 {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return 0f32;
+        }
+        self.data[(y as u32 * self.width + x as u32) as usize]
+    }
+
+    /// Grow the covered area of this mask by the given `radius` in pixels,
+    /// which is used to render an outline.
+    ///
+    /// Each pixel takes the maximum coverage within a disc of the given radius around it.
+    /// The disc is covered by a horizontal span on each row it crosses,
+    /// so the maximum is taken over each span with a sliding window.
+    fn dilate(&self, radius: u32) -> Mask {
+        let mut out = Mask::new(self.width, self.height);
+        let (width, height, r) = (self.width as usize, self.height as i64, radius as i64);
+
+        for y in 0..height {
+            let target = &mut out.data[y as usize * width..(y as usize + 1) * width];
+            for dy in -r..r + 1 {
+                let source = y + dy;
+                if source < 0 || source >= height {
+                    continue;
+                }
+
+                let span = ((r * r - dy * dy) as f64).sqrt() as usize;
+                let row = &self.data[source as usize * width..(source as usize + 1) * width];
+                max_window(row, span, target);
+            }
+        }
+
+        out
+    }
+
+    /// Draw the mask on the given `img` in the given `color`,
+    /// with the top-left corner of the mask at `left` and `top`.
+    fn draw(&self, img: &mut RgbaImage, left: i64, top: i64, color: &Color) {
+        let (width, height) = img.dimensions();
+        let pixel = color.to_rgba();
+
+        for y in 0..self.height as i64 {
+            for x in 0..self.width as i64 {
+                let (target_x, target_y) = (left + x, top + y);
+                if target_x < 0 || target_y < 0 || target_x >= width as i64 || target_y >= height as i64 {
+                    continue;
+                }
+
+                let coverage = self.get(x, y);
+                if coverage <= 0f32 {
+                    continue;
+                }
+
+                let target = img.get_pixel_mut(target_x as u32, target_y as u32);
+                *target = blend(target, &pixel, coverage);
+            }
+        }
+    }
+}
+
+/// Raise each value in `out` to the maximum value in `row` within `radius` positions of it.
+///
+/// This uses a sliding window of decreasing values, so each value of the row is only visited once.
+fn max_window(row: &[f32], radius: usize, out: &mut [f32]) {
+    let mut window: VecDeque<usize> = VecDeque::new();
+    let mut next = 0;
+
+    for (x, value) in out.iter_mut().enumerate() {
+        // Add the values entering the window, dropping smaller ones that can't be the maximum
+        while next < row.len() && next <= x + radius {
+            while let Some(&last) = window.back() {
+                if row[last] > row[next] {
+                    break;
+                }
+                window.pop_back();
+            }
+            window.push_back(next);
+            next += 1;
+        }
+
+        // Drop the values that left the window
+        while let Some(&first) = window.front() {
+            if first + radius >= x {
+                break;
+            }
+            window.pop_front();
+        }
+
+        if let Some(&first) = window.front() {
+            *value = value.max(row[first]);
+        }
+    }
+}
+
+impl Text {
+    pub fn new() -> Text {
+        Text {
+            properties: PROPERTIES.clone()
+        }
+    }
+
+    /// Load the configured font, or the bundled default font if none is configured.
+    fn load_font(&self) -> Result<Font<'static>> {
+        // Use the bundled font if no font path is given
+        let path = self.property(PROP_FONT).unwrap().as_str().unwrap();
+        if path.trim().to_lowercase() == FONT_DEFAULT {
+            return Font::from_bytes(DEFAULT_FONT)
+                .map_err(|err| Error::filter("Failed to load the bundled font").caused_by(err));
+        }
+
+        // Read the font file
+        let path = shellexpand::tilde(path.trim()).to_string();
+        let mut data = Vec::new();
+        File::open(&path)
+            .and_then(|mut file| file.read_to_end(&mut data))
+            .map_err(|err| Error::filter(format!("Failed to read font file {:?}", path)).caused_by(err))?;
+
+        Font::from_bytes(data)
+            .map_err(|err| Error::filter("Failed to load font, the file might not be a TrueType font").caused_by(err))
+    }
+
+    /// Render the given `text` with the given `font` and `size` into a coverage mask.
+    ///
+    /// The text is surrounded by a transparent border with a width of `padding` pixels.
+    fn render(text: &str, font: &Font, size: f32, padding: u32) -> Mask {
+        let scale = Scale::uniform(size);
+        let v_metrics = font.v_metrics(scale);
+
+        // Layout the glyphs, and determine the text dimensions
+        let glyphs: Vec<_> = font
+            .layout(text, scale, point(padding as f32, padding as f32 + v_metrics.ascent))
+            .collect();
+        let text_width = glyphs.last()
+            .map(|g| g.position().x - padding as f32 + g.unpositioned().h_metrics().advance_width)
+            .unwrap_or(0f32)
+            .ceil() as u32;
+        let text_height = (v_metrics.ascent - v_metrics.descent).ceil() as u32;
+
+        // Draw each glyph into the mask
+        let mut mask = Mask::new(text_width + padding * 2, text_height + padding * 2);
+        for glyph in glyphs {
+            if let Some(bounds) = glyph.pixel_bounding_box() {
+                glyph.draw(|x, y, coverage| {
+                    let x = x as i64 + bounds.min.x as i64;
+                    let y = y as i64 + bounds.min.y as i64;
+                    if x >= 0 && y >= 0 && x < mask.width as i64 && y < mask.height as i64 {
+                        let i = (y as u32 * mask.width + x as u32) as usize;
+                        mask.data[i] = mask.data[i].max(coverage);
+                    }
+                });
+            }
+        }
+
+        mask
+    }
+}
+
+/// Expand all placeholders in the given `text`.
+///
+/// The following placeholders are supported:
+/// - `{hostname}`: the hostname of this machine.
+/// - `{user}`: the name of the current user.
+/// - `{date}`: the current date and time, or `{date:FORMAT}` with a `strftime` format.
+///
+/// An error is returned if a placeholder is unknown or malformed.
+pub fn expand_placeholders(text: &str) -> Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        // Copy everything before the placeholder
+        out.push_str(&rest[..start]);
+
+        // Find the end of the placeholder
+        let end = match rest[start..].find('}') {
+            Some(end) => end + start,
+            None => return Err(Error::filter(format!(
+                "Unclosed placeholder '{}' in text, missing '}}'",
+                &rest[start..],
+            ))),
+        };
+        let placeholder = &rest[start + 1..end];
+
+        // Split the placeholder name and argument
+        let mut parts = placeholder.splitn(2, ':');
+        let name = parts.next().unwrap().trim();
+        let arg = parts.next();
+
+        match name {
+            "hostname" => out.push_str(&hostname()),
+            "user" => out.push_str(
+                &env::var("USER")
+                    .or(env::var("LOGNAME"))
+                    .unwrap_or("".into())
+            ),
+            "date" => write!(out, "{}", Local::now().format(arg.unwrap_or(DATE_FORMAT)))
+                .map_err(|err| Error::filter(format!("Invalid date format in text placeholder '{{{}}}'", placeholder))
+                    .caused_by(err))?,
+            _ => return Err(Error::filter(format!(
+                "Unknown placeholder '{{{}}}' in text, use {{hostname}}, {{user}} or {{date}}",
+                placeholder,
+            ))),
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Get the hostname of this machine.
+///
+/// An empty string is returned if the hostname could not be determined.
+fn hostname() -> String {
+    for path in &["/proc/sys/kernel/hostname", "/etc/hostname"] {
+        let mut name = String::new();
+        if File::open(path).and_then(|mut file| file.read_to_string(&mut name)).is_ok() {
+            return name.trim().into();
+        }
+    }
+
+    env::var("HOSTNAME").unwrap_or("".into())
+}
+
+impl ImgProc for Text {
+    fn process(&self, img: ImgEdit) -> Result<ImgEdit> {
+        // Get the text to draw, and the styling properties
+        let text = expand_placeholders(&self.property(PROP_TEXT).unwrap().as_str().unwrap())?;
+        let size = self.property(PROP_SIZE).unwrap().as_float().unwrap();
+        let color = self.property(PROP_COLOR).unwrap().as_color().unwrap();
+        let gravity = Gravity::parse(&self.property(PROP_GRAVITY).unwrap().as_str().unwrap())?;
+        let shadow = self.property(PROP_SHADOW).unwrap().as_bool().unwrap_or(false);
+        let outline = self.property(PROP_OUTLINE).unwrap().as_uint().unwrap();
+
+        // Render the text into a mask, with room for the outline and shadow
+        let font = self.load_font()?;
+        let shadow_offset = if shadow { cmp::max(1, (size / 16f32).round() as u32) } else { 0 };
+        let padding = outline + shadow_offset;
+        let mask = Text::render(&text, &font, size, padding);
+
+        // Determine where to place the text, excluding the padding
+        let mut buf = img.into_img().to_rgba();
+        let (width, height) = buf.dimensions();
+        let (left, top) = gravity.position(
+            buf.dimensions(),
+            (mask.width - padding * 2, mask.height - padding * 2),
+            (
+                self.property(PROP_X).unwrap().as_length().unwrap().resolve(width, (width, height)),
+                self.property(PROP_Y).unwrap().as_length().unwrap().resolve(height, (width, height)),
+            ),
+        );
+        let (left, top) = (left - padding as i64, top - padding as i64);
+
+        // Draw the shadow, outline and the text itself
+        let outline_mask = if outline > 0 { Some(mask.dilate(outline)) } else { None };
+        if shadow {
+            outline_mask.as_ref().unwrap_or(&mask).draw(
+                &mut buf,
+                left + shadow_offset as i64,
+                top + shadow_offset as i64,
+                &Color::new(0, 0, 0, 160),
+            );
+        }
+        if let Some(ref outline_mask) = outline_mask {
+            outline_mask.draw(&mut buf, left, top, &Color::new(0, 0, 0, 255));
+        }
+        mask.draw(&mut buf, left, top, &color);
+
+        Ok(ImgEdit::from(DynamicImage::ImageRgba8(buf)))
+    }
+
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
+        &self.properties
+    }
+
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
+        &mut self.properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chrono::Local;
+
+    use super::{expand_placeholders, max_window, Mask};
+
+    /// Grow the covered area of the given `mask` by scanning the full disc around each pixel.
+    fn dilate_disc(mask: &Mask, radius: u32) -> Vec<f32> {
+        let r = radius as i64;
+        let mut out = vec![];
+        for y in 0..mask.height as i64 {
+            for x in 0..mask.width as i64 {
+                let mut max = 0f32;
+                for dy in -r..r + 1 {
+                    for dx in -r..r + 1 {
+                        if dx * dx + dy * dy <= r * r {
+                            max = max.max(mask.get(x + dx, y + dy));
+                        }
+                    }
+                }
+                out.push(max);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn max_window_spans() {
+        let row = [0.0, 0.5, 0.0, 0.0, 1.0, 0.0, 0.25];
+        let mut out = [0f32; 7];
+        max_window(&row, 0, &mut out);
+        assert_eq!(out, row);
+
+        let mut out = [0.75, 0f32, 0.0, 0.0, 0.0, 0.0, 0.0];
+        max_window(&row, 1, &mut out);
+        assert_eq!(out, [0.75, 0.5, 0.5, 1.0, 1.0, 1.0, 0.25]);
+    }
+
+    #[test]
+    fn dilate() {
+        let mut mask = Mask::new(13, 9);
+        for &(x, y, coverage) in &[(6, 4, 1f32), (1, 1, 0.5), (12, 8, 0.25), (7, 4, 0.75)] {
+            mask.data[(y * mask.width + x) as usize] = coverage;
+        }
+
+        for radius in 0..6 {
+            assert_eq!(mask.dilate(radius).data, dilate_disc(&mask, radius), "radius {}", radius);
+        }
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(expand_placeholders("").unwrap(), "");
+        assert_eq!(expand_placeholders("Locked, type to unlock").unwrap(), "Locked, type to unlock");
+        assert_eq!(expand_placeholders("{date:%Y}").unwrap(), Local::now().format("%Y").to_string());
+        assert_eq!(
+            expand_placeholders("year { date : %Y}!").unwrap(),
+            format!("year {}!", Local::now().format(" %Y")),
+        );
+        assert!(!expand_placeholders("{hostname}").unwrap().contains('{'));
+    }
+
+    #[test]
+    fn placeholder_errors() {
+        let err = expand_placeholders("hello {name}").unwrap_err().to_string();
+        assert!(err.contains("'{name}'"), "{}", err);
+
+        let err = expand_placeholders("it is {date:%H:%M").unwrap_err().to_string();
+        assert!(err.contains("'{date:%H:%M'"), "{}", err);
+        assert!(err.contains("missing '}'"), "{}", err);
+    }
+}
@@ -3,25 +3,29 @@ use std::collections::HashMap;
 use err::Result;
 use img::ImgEdit;
 
-use super::{ImgProc, Prop};
+use super::{ImgProc, Prop, Property};
 
 // Property names
 pub static PROP_SIGMA: &'static str = "sigma";
-pub static PROP_THRESHOLD: &'static str = "sigma";
+pub static PROP_THRESHOLD: &'static str = "threshold";
 
 // Default properties
 lazy_static! {
-    static ref PROPERTIES: HashMap<&'static str, Prop> = {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
         let mut map = HashMap::new();
-        map.insert(PROP_SIGMA, Prop::Float(None));
-        map.insert(PROP_THRESHOLD, Prop::Int(None));
+        map.insert(PROP_SIGMA, Property::new(Prop::Float(None), "Amount to blur the image by for sharpening.")
+            .required()
+            .min(0.0));
+        map.insert(PROP_THRESHOLD, Property::new(Prop::Int(None), "Minimum brightness difference to sharpen.")
+            .required()
+            .min(0.0));
         map
     };
 }
 
 /// Image unsharpen processor.
 pub struct Unsharpen {
-    properties: HashMap<&'static str, Prop>
+    properties: HashMap<&'static str, Property>
 }
 
 impl Unsharpen {
@@ -44,11 +48,11 @@ impl ImgProc for Unsharpen {
         ))
     }
 
-    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Prop> {
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
         &self.properties
     }
 
-    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Prop> {
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
         &mut self.properties
     }
 }
\ No newline at end of file
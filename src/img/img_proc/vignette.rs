@@ -0,0 +1,143 @@
+extern crate image;
+
+use std::collections::HashMap;
+
+use self::image::DynamicImage;
+
+use err::Result;
+use img::{Color, ImgEdit};
+use img::color::blend;
+use util;
+
+use super::{ImgProc, Prop, Property};
+
+// Property names
+pub static PROP_STRENGTH: &'static str = "strength";
+pub static PROP_RADIUS: &'static str = "radius";
+pub static PROP_SOFTNESS: &'static str = "softness";
+pub static PROP_COLOR: &'static str = "color";
+
+// Default properties
+lazy_static! {
+    static ref PROPERTIES: HashMap<&'static str, Property> = {
+        let mut map = HashMap::new();
+        map.insert(PROP_STRENGTH, Property::new(Prop::Float(Some(0.8)), "How far the edges fade towards the color.")
+            .min(0.0)
+            .max(1.0));
+        map.insert(PROP_RADIUS, Property::new(Prop::Float(Some(0.5)), "Distance from the center where fading starts.").min(0.0));
+        map.insert(PROP_SOFTNESS, Property::new(Prop::Float(Some(0.5)), "Distance over which the edges fade.").min(0.0));
+        map.insert(PROP_COLOR, Property::new(Prop::Color(Some(Color::new(0, 0, 0, 255))), "Color to fade towards."));
+        map
+    };
+}
+
+/// Image vignette processor.
+///
+/// This fades the edges of the image towards a color, drawing attention to the center.
+pub struct Vignette {
+    properties: HashMap<&'static str, Property>
+}
+
+impl Vignette {
+    pub fn new() -> Vignette {
+        Vignette {
+            properties: PROPERTIES.clone()
+        }
+    }
+}
+
+/// Smoothly interpolate between 0 and 1 when `x` moves from `edge0` to `edge1`.
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge1 <= edge0 {
+        return if x < edge0 { 0f32 } else { 1f32 };
+    }
+
+    let t = util::clamp((x - edge0) / (edge1 - edge0), 0f32, 1f32);
+    t * t * (3f32 - 2f32 * t)
+}
+
+impl ImgProc for Vignette {
+    fn process(&self, img: ImgEdit) -> Result<ImgEdit> {
+        // Get the vignette properties
+        let strength = self.property(PROP_STRENGTH).unwrap().as_float().unwrap();
+        let radius = self.property(PROP_RADIUS).unwrap().as_float().unwrap();
+        let softness = self.property(PROP_SOFTNESS).unwrap().as_float().unwrap();
+        let color = self.property(PROP_COLOR).unwrap().as_color().unwrap().to_rgba();
+
+        // Get the image as RGBA buffer, and find it's center
+        let mut buf = img.into_img().to_rgba();
+        let (width, height) = buf.dimensions();
+        let (center_x, center_y) = (width as f32 / 2f32, height as f32 / 2f32);
+
+        // Fade each pixel based on it's distance to the center,
+        // where the distance is 0 in the center and 1 in the corners
+        for (x, y, pixel) in buf.enumerate_pixels_mut() {
+            let dx = (x as f32 + 0.5 - center_x) / center_x;
+            let dy = (y as f32 + 0.5 - center_y) / center_y;
+            let distance = (dx * dx + dy * dy).sqrt() / 2f32.sqrt();
+
+            let amount = smoothstep(radius, radius + softness, distance) * strength;
+            if amount > 0f32 {
+                *pixel = blend(pixel, &color, amount);
+            }
+        }
+
+        Ok(ImgEdit::from(DynamicImage::ImageRgba8(buf)))
+    }
+
+    fn properties<'a: 'b, 'b>(&'a self) -> &'b HashMap<&'static str, Property> {
+        &self.properties
+    }
+
+    fn mut_properties<'a: 'b, 'b>(&'a mut self) -> &'b mut HashMap<&'static str, Property> {
+        &mut self.properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::image::{DynamicImage, GenericImage, Rgba, RgbaImage};
+
+    use img::ImgEdit;
+    use img::img_proc::ImgProc;
+
+    use super::{smoothstep, Vignette, PROP_COLOR, PROP_RADIUS, PROP_SOFTNESS, PROP_STRENGTH};
+
+    /// Apply the vignette with the given properties to a 10x10 white image.
+    fn vignette(props: &[(&str, &str)]) -> DynamicImage {
+        let mut vignette = Vignette::new();
+        for &(name, value) in props {
+            vignette.set_property(name, value).unwrap();
+        }
+        let img = RgbaImage::from_pixel(10, 10, Rgba([255, 255, 255, 255]));
+        vignette.process_safe(ImgEdit::from(DynamicImage::ImageRgba8(img))).unwrap().into_img()
+    }
+
+    #[test]
+    fn fades_edges() {
+        let img = vignette(&[]);
+        assert_eq!(img.dimensions(), (10, 10));
+
+        // The center is untouched, the corners at a distance of 0.9 fade by 0.8 * 0.896
+        assert_eq!(img.get_pixel(5, 5), Rgba([255, 255, 255, 255]));
+        assert_eq!(img.get_pixel(0, 0), Rgba([72, 72, 72, 255]));
+        assert_eq!(img.get_pixel(9, 9), img.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn full_strength() {
+        let img = vignette(&[(PROP_STRENGTH, "1"), (PROP_RADIUS, "0"), (PROP_SOFTNESS, "0"), (PROP_COLOR, "red")]);
+        assert_eq!(img.dimensions(), (10, 10));
+        assert_eq!(img.get_pixel(5, 5), Rgba([255, 0, 0, 255]));
+        assert_eq!(img.get_pixel(0, 9), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn smoothstep_range() {
+        assert_eq!(smoothstep(0.5, 1.0, 0.2), 0.0);
+        assert_eq!(smoothstep(0.5, 1.0, 0.75), 0.5);
+        assert_eq!(smoothstep(0.5, 1.0, 2.0), 1.0);
+        assert_eq!(smoothstep(0.5, 0.5, 0.4), 0.0);
+        assert_eq!(smoothstep(0.5, 0.5, 0.5), 1.0);
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use err::{Error, Result};
+
+/// A length, such as a position or size, that may be relative to the image it is used on.
+///
+/// This allows geometric properties to be configured independent of the screen resolution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Length {
+    /// An absolute length in pixels.
+    Px(f32),
+
+    /// A percentage of the image dimension on the same axis.
+    Percent(f32),
+
+    /// A percentage of the image width.
+    Vw(f32),
+
+    /// A percentage of the image height.
+    Vh(f32),
+}
+
+impl Length {
+    /// Parse a length from the given `value`.
+    ///
+    /// The following formats are supported:
+    /// - `100` or `100px`: an absolute length in pixels
+    /// - `50%`: a percentage of the image width or height, depending on the property
+    /// - `50vw` and `50vh`: a percentage of the image width or height
+    ///
+    /// Lengths may be negative, for offsets moving in the opposite direction.
+    ///
+    /// An error is returned if the value could not be parsed.
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim().to_lowercase();
+
+        // Split the unit from the number
+        let (number, unit): (&str, fn(f32) -> Length) = if value.ends_with("px") {
+            (&value[..value.len() - 2], Length::Px)
+        } else if value.ends_with('%') {
+            (&value[..value.len() - 1], Length::Percent)
+        } else if value.ends_with("vw") {
+            (&value[..value.len() - 2], Length::Vw)
+        } else if value.ends_with("vh") {
+            (&value[..value.len() - 2], Length::Vh)
+        } else {
+            (&value[..], Length::Px)
+        };
+
+        number.trim()
+            .parse::<f32>()
+            .map(unit)
+            .map_err(|_| Error::filter("Invalid length, use a number of pixels, or a number followed by px, %, vw or vh"))
+    }
+
+    /// Get the numeric value of the length, without it's unit.
+    pub fn value(&self) -> f32 {
+        match *self {
+            Length::Px(x) | Length::Percent(x) | Length::Vw(x) | Length::Vh(x) => x,
+        }
+    }
+
+    /// Resolve the length to a number of pixels, for an image with the given `dimensions`.
+    ///
+    /// The `reference` is the image dimension a percentage is relative to,
+    /// being the width or height of the image depending on the property.
+    pub fn resolve(&self, reference: u32, dimensions: (u32, u32)) -> i32 {
+        let pixels = match *self {
+            Length::Px(x) => x,
+            Length::Percent(x) => x / 100f32 * reference as f32,
+            Length::Vw(x) => x / 100f32 * dimensions.0 as f32,
+            Length::Vh(x) => x / 100f32 * dimensions.1 as f32,
+        };
+
+        pixels.round() as i32
+    }
+
+    /// Resolve the length to a number of pixels like `resolve`, which must not be negative.
+    ///
+    /// If the resolved length is negative, `None` is returned.
+    pub fn resolve_uint(&self, reference: u32, dimensions: (u32, u32)) -> Option<u32> {
+        let pixels = self.resolve(reference, dimensions);
+        if pixels >= 0 { Some(pixels as u32) } else { None }
+    }
+}
+
+impl Display for Length {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            Length::Px(x) => write!(f, "{}px", x),
+            Length::Percent(x) => write!(f, "{}%", x),
+            Length::Vw(x) => write!(f, "{}vw", x),
+            Length::Vh(x) => write!(f, "{}vh", x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Length;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Length::parse("100").unwrap(), Length::Px(100f32));
+        assert_eq!(Length::parse("100px").unwrap(), Length::Px(100f32));
+        assert_eq!(Length::parse("12.5%").unwrap(), Length::Percent(12.5));
+        assert_eq!(Length::parse("50vw").unwrap(), Length::Vw(50f32));
+        assert_eq!(Length::parse("50vh").unwrap(), Length::Vh(50f32));
+    }
+
+    #[test]
+    fn parse_negative() {
+        assert_eq!(Length::parse("-20").unwrap(), Length::Px(-20f32));
+        assert_eq!(Length::parse("-5%").unwrap(), Length::Percent(-5f32));
+        assert_eq!(Length::parse("-1.5vh").unwrap(), Length::Vh(-1.5));
+    }
+
+    #[test]
+    fn parse_whitespace_and_case() {
+        assert_eq!(Length::parse("  10PX ").unwrap(), Length::Px(10f32));
+        assert_eq!(Length::parse("50 %").unwrap(), Length::Percent(50f32));
+        assert_eq!(Length::parse("25 VW").unwrap(), Length::Vw(25f32));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for value in &["", "px", "%", "10em", "10pt", "ten", "10px%", "1,5"] {
+            assert!(Length::parse(value).is_err(), "'{}' should be invalid", value);
+        }
+    }
+
+    #[test]
+    fn resolve() {
+        let dimensions = (1920, 1080);
+        assert_eq!(Length::Px(42f32).resolve(1080, dimensions), 42);
+        assert_eq!(Length::Percent(50f32).resolve(1080, dimensions), 540);
+        assert_eq!(Length::Percent(50f32).resolve(1920, dimensions), 960);
+        assert_eq!(Length::Vw(10f32).resolve(1080, dimensions), 192);
+        assert_eq!(Length::Vh(10f32).resolve(1920, dimensions), 108);
+        assert_eq!(Length::Percent(-10f32).resolve(1920, dimensions), -192);
+    }
+
+    #[test]
+    fn resolve_rounds() {
+        let dimensions = (100, 100);
+        assert_eq!(Length::Px(1.4).resolve(100, dimensions), 1);
+        assert_eq!(Length::Px(1.5).resolve(100, dimensions), 2);
+        assert_eq!(Length::Px(-1.5).resolve(100, dimensions), -2);
+        assert_eq!(Length::Percent(33.3).resolve(10, dimensions), 3);
+        assert_eq!(Length::Vw(0.5).resolve(100, (101, 100)), 1);
+    }
+
+    #[test]
+    fn resolve_uint() {
+        let dimensions = (200, 100);
+        assert_eq!(Length::Percent(25f32).resolve_uint(200, dimensions), Some(50));
+        assert_eq!(Length::Px(0f32).resolve_uint(200, dimensions), Some(0));
+        assert_eq!(Length::Px(-0.4).resolve_uint(200, dimensions), Some(0));
+        assert_eq!(Length::Px(-0.5).resolve_uint(200, dimensions), None);
+        assert_eq!(Length::Vh(-10f32).resolve_uint(200, dimensions), None);
+    }
+
+    #[test]
+    fn display() {
+        for value in &["10px", "12.5%", "-50vw", "3vh"] {
+            assert_eq!(Length::parse(value).unwrap().to_string(), *value);
+        }
+    }
+}
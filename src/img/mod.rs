@@ -1,7 +1,13 @@
+pub mod color;
+pub mod gravity;
 pub mod img;
 pub mod img_edit;
 pub mod img_proc;
+pub mod length;
 
 // Reexport modules
+pub use self::color::Color;
+pub use self::gravity::Gravity;
 pub use self::img::Img;
 pub use self::img_edit::ImgEdit;
+pub use self::length::Length;
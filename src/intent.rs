@@ -1,109 +1,211 @@
+extern crate base64;
 extern crate config;
 
-use std::cmp;
 use std::collections::BTreeMap;
-use std::process::Command;
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
 
+use app;
 use cmd;
 use config::Config;
 use err::{Error, Result};
+use lock::{Backend, BackendSelector, LockCommand, Settings};
+use shell;
+
+/// Maximum line length of base64 data embedded in scripts.
+const BASE64_LINE_LEN: usize = 76;
+
+/// Shell variable holding the path of the extracted image in scripts.
+const SCRIPT_IMAGE_VAR: &'static str = "$image";
+
+/// An image to show on the lock screen.
+enum LockImage {
+    /// An image file at the given path.
+    File(String),
+
+    /// Raw BGRX pixel data with the given dimensions, that is piped to the lock screen.
+    Raw(Vec<u8>, (u32, u32)),
+}
 
 /// Application intent, defining what this application instance is used for.
 ///
-/// For example; this struct keeps track of the command and it's arguments to run when invoking the
-/// lock screen.
+/// For example; this struct keeps track of the lock screen backend and the command to run when
+/// invoking the lock screen.
 ///
 /// This struct is usually built while processing configurations and arguments, and is used as
 /// reference when finishing.
 pub struct Intent {
-    /// A list of commands to run.
-    cmd: Vec<String>,
+    /// The lock screen backend to invoke.
+    backend: Box<Backend>,
+
+    /// The command for the common lock screen settings.
+    settings: LockCommand,
+
+    /// The command for the configured tool specific parameters.
+    ///
+    /// These are left out when the lock screen fails with them, so the screen is always locked.
+    params: LockCommand,
+
+    /// The image to show on the lock screen.
+    image: Option<LockImage>,
 }
 
 impl Intent {
-    /// Construct the intent with the default properties.
-    pub fn new() -> Self {
+    /// Construct the intent for the given lock screen `backend`, with the default settings.
+    pub fn new(backend: Box<Backend>) -> Self {
         Intent {
-            cmd: vec!["i3lock".into()],
+            settings: backend.settings(&Settings::default()),
+            backend,
+            params: LockCommand::new(),
+            image: None,
         }
     }
 
     /// Construct the intent based on a given configuration.
+    ///
+    /// The configured lock screen backend is used, or one is detected if not configured.
     pub fn from(config: &Config) -> Result<Self> {
-        let mut intent = Self::new();
+        let backend = BackendSelector::select(
+            config.get(cmd::ARG_LOCK_BACKEND).and_then(|name| name.as_str())
+        )?;
+        let mut intent = Self::new(backend);
 
-        // Get the current list of arguments or create a fresh one if non-existent
-        let params = config.get_dict(cmd::ARG_PARAM, BTreeMap::new());
+        // Translate the common settings, and the tool specific parameters,
+        // invalid ones are left out with a warning so the screen is always locked
+        intent.settings = intent.backend.settings(&Settings::from(config));
+        let params = config.get_dict(cmd::ARG_PARAM, BTreeMap::new()).unwrap_or_else(|err| {
+            eprintln!("{} warning: ignoring the lock screen parameters\n  caused by: {}", app::NAME, err);
+            BTreeMap::new()
+        });
+        intent.params = intent.backend.params(&params);
 
-        // Process all i3 parameters
-        for (mut arg, base_val) in params.unwrap() {
-            // Prepend 1 or 2 argument hyphens if missing
-            if !arg.starts_with("-") {
-                for _ in 0..cmp::min(arg.len(), 2) {
-                    arg.insert(0, '-');
-                }
-            }
+        Ok(intent)
+    }
 
-            // Define an optional value variable
-            let mut val: Option<String> = None;
-
-            // Parse argument values if set
-            if !base_val.is_empty() {
-                // Determine whether to attach the argument and value with an equals sign,
-                // or whether to separate them with a space.
-                if arg.len() <= 2 {
-                    val = Some(base_val);
-                } else {
-                    arg.push('=');
-                    arg.push_str(&base_val);
-                }
-            }
+    /// Get the name of the lock screen backend.
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
 
-            // Push the arguments to the intent
-            intent.push_arg(arg);
-            if val.is_some() {
-                intent.push_arg(val.unwrap());
-            }
+    /// Set the path of the image to show on the lock screen.
+    pub fn set_image(&mut self, path: String) {
+        self.image = Some(LockImage::File(path));
+    }
+
+    /// Check whether the lock screen supports raw images, see `set_raw_image`.
+    pub fn supports_raw(&self) -> bool {
+        self.backend.raw_image((1, 1)).is_some()
+    }
+
+    /// Set the raw BGRX pixel `data` of the image to show on the lock screen,
+    /// with the given `dimensions`.
+    ///
+    /// The data is piped to the lock screen when it is invoked,
+    /// so the image never has to be written to disk.
+    pub fn set_raw_image(&mut self, data: Vec<u8>, dimensions: (u32, u32)) {
+        self.image = Some(LockImage::Raw(data, dimensions));
+    }
+
+    /// Build the full command, showing the given `image`.
+    ///
+    /// If `params` is false, the configured tool specific parameters are left out.
+    fn build(&self, image: Option<&LockImage>, params: bool) -> LockCommand {
+        let mut cmd = LockCommand::new();
+        cmd.arg(self.backend.binary());
+        cmd.extend(&self.settings);
+        if params {
+            cmd.extend(&self.params);
         }
 
-        Ok(intent)
+        // Show the image
+        let image = match image {
+            Some(LockImage::File(path)) => Some(self.backend.image(path)),
+            Some(LockImage::Raw(_, dimensions)) => self.backend.raw_image(*dimensions),
+            None => None,
+        };
+        if let Some(image) = image {
+            cmd.extend(&image);
+        }
+
+        cmd
     }
 
-    /// Put an additional argument into the intent.
-    pub fn push_arg(&mut self, arg: String) {
-        self.cmd.push(arg);
+    /// Check whether any tool specific parameters are configured.
+    fn has_params(&self) -> bool {
+        !self.params.args.is_empty() || !self.params.env.is_empty()
     }
 
-    /// Invoke i3lock with this intent.
+    /// Invoke the lock screen with this intent.
+    ///
+    /// If the lock screen fails with the configured parameters, it is invoked again without them,
+    /// so the screen is still locked.
     pub fn run(&self) -> Result<()> {
-        println!("Starting i3lock...");
+        match self.invoke(true) {
+            Err(ref err) if self.has_params() => {
+                eprintln!(
+                    "{} warning: {}\nRetrying without {} parameters",
+                    app::NAME,
+                    err,
+                    self.backend.name(),
+                );
+                self.invoke(false)
+            },
+            result => result,
+        }
+    }
+
+    /// Invoke the lock screen with this intent, with or without the configured `params`.
+    fn invoke(&self, params: bool) -> Result<()> {
+        let name = self.backend.name();
+        println!("Starting {}...", name);
 
-        // Invoke i3lock
-        let mut args_iter = self.cmd.iter();
-        let out = Command::new(args_iter.next().unwrap())
-            .args(args_iter)
-            .output()
-            .expect("Failed to invoke i3lock");
+        // Invoke the lock screen
+        let cmd = self.build(self.image.as_ref(), params);
+        let mut child = Command::new(&cmd.args[0])
+            .args(&cmd.args[1..])
+            .envs(cmd.env.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| Error::lock(format!("Failed to invoke {}", name)).caused_by(err))?;
 
-        // Wait for i3lock to complete, handle non-zero status codes
+        // Pipe a raw image to the lock screen, it reports an error itself if it quits early
+        {
+            let stdin = child.stdin.take();
+            if let (Some(mut stdin), &Some(LockImage::Raw(ref data, _))) = (stdin, &self.image) {
+                match stdin.write_all(data) {
+                    Err(ref err) if err.kind() == ErrorKind::BrokenPipe => {},
+                    result => result.map_err(|err| Error::lock(
+                        format!("Failed to pipe the lock image to {}", name)
+                    ).caused_by(err))?,
+                }
+            }
+        }
+
+        let out = child.wait_with_output()
+            .map_err(|err| Error::lock(format!("Failed to invoke {}", name)).caused_by(err))?;
+
+        // Wait for the lock screen to complete, handle non-zero status codes
         if out.status.success() {
-            println!("i3lock exited successfully");
+            println!("{} exited successfully", name);
         } else {
             println!(
-                "i3lock exited with a non-zero status code (code: {})",
-                out.status.code().unwrap()
+                "{} exited with a non-zero status code (code: {})",
+                name,
+                out.status.code().map(|code| code.to_string()).unwrap_or("none".into()),
             );
         }
 
-        // Print stdout and stderr from i3lock if not empty
+        // Print stdout and stderr from the lock screen if not empty
         if !out.stdout.is_empty() {
-            println!("\ni3lock stdout:");
+            println!("\n{} stdout:", name);
             println!("==========");
             println!("{}", String::from_utf8_lossy(&out.stdout));
             println!("==========");
         }
         if !out.stderr.is_empty() {
-            println!("\ni3lock stderr:");
+            println!("\n{} stderr:", name);
             println!("==========");
             println!("{}", String::from_utf8_lossy(&out.stderr));
             println!("==========");
@@ -111,16 +213,76 @@ impl Intent {
 
         // Return errors
         if !out.status.success() {
-            return Err(Error::new("i3lock exited with a non-zero status code"));
+            return Err(Error::lock(format!("{} exited with a non-zero status code", name)));
         }
 
         Ok(())
     }
 
-    /// Build the command that is run when i3lock is normally invoked and return it.
+    /// Build the command that is run when the lock screen is normally invoked and return it.
+    ///
+    /// Environment variables and arguments are quoted where needed,
+    /// so the command can safely be pasted in a POSIX shell.
     ///
-    /// This function does not invoke i3lock.
+    /// This function does not invoke the lock screen.
     pub fn command(&self) -> String {
-        self.cmd.join(" ")
+        Intent::format(&self.build(self.image.as_ref(), true), None)
     }
-}
\ No newline at end of file
+
+    /// Format the given command as a POSIX shell command line.
+    ///
+    /// If a shell `var` is given, each occurrence of it is expanded by the shell.
+    fn format(cmd: &LockCommand, var: Option<&str>) -> String {
+        let quote = |arg: &str| match var {
+            Some(var) => shell::quote_expanding(arg, var),
+            None => shell::quote(arg),
+        };
+
+        cmd.env.iter()
+            .map(|(key, value)| format!("{}={}", key, quote(value)))
+            .chain(cmd.args.iter().map(|arg| quote(arg)))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Build a standalone POSIX shell script that invokes the lock screen with this intent.
+    ///
+    /// If `image` data is given, it is embedded in the script and passed to the lock screen,
+    /// so the script can show the same lock screen without this application being installed.
+    pub fn script(&self, image: Option<&[u8]>) -> String {
+        let mut script = format!(
+            "#!/bin/sh\n\
+            # Lock screen generated by {} {}\n\
+            set -e\n",
+            app::NAME,
+            app::VERSION,
+        );
+
+        // Embed the image, and extract it to a temporary file when the script runs
+        if let Some(image) = image {
+            script.push_str("\n# Extract the embedded lock image\n");
+            script.push_str("image=\"$(mktemp)\"\n");
+            script.push_str("trap 'rm -f \"$image\"' EXIT\n");
+            script.push_str("base64 -d > \"$image\" <<'IMAGE'\n");
+            let encoded = base64::encode(image);
+            for line in encoded.as_bytes().chunks(BASE64_LINE_LEN) {
+                script.push_str(&String::from_utf8_lossy(line));
+                script.push('\n');
+            }
+            script.push_str("IMAGE\n");
+        }
+
+        // Lock without the parameters if the lock screen doesn't support them
+        let image = image.map(|_| LockImage::File(SCRIPT_IMAGE_VAR.into()));
+        let image = image.as_ref();
+        let var = Some(SCRIPT_IMAGE_VAR);
+        script.push('\n');
+        script.push_str(&Intent::format(&self.build(image, true), var));
+        if self.has_params() {
+            script.push_str(" || \\\n    ");
+            script.push_str(&Intent::format(&self.build(image, false), var));
+        }
+        script.push('\n');
+        script
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use app;
+use err::Error;
+use util;
+
+use super::Settings;
+
+/// A lock screen command, being the environment variables and arguments to invoke a tool with.
+#[derive(Clone, Debug, Default)]
+pub struct LockCommand {
+    /// Environment variables to set, as key and value.
+    pub env: Vec<(String, String)>,
+
+    /// Arguments to pass.
+    pub args: Vec<String>,
+}
+
+impl LockCommand {
+    /// Construct an empty command.
+    pub fn new() -> Self {
+        LockCommand::default()
+    }
+
+    /// Add the given argument.
+    pub fn arg<S: Into<String>>(&mut self, arg: S) -> &mut Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Set the environment variable with the given `key` to `value`.
+    pub fn env<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) -> &mut Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Append the environment variables and arguments of the `other` command.
+    pub fn extend(&mut self, other: &LockCommand) -> &mut Self {
+        self.env.extend(other.env.iter().cloned());
+        self.args.extend(other.args.iter().cloned());
+        self
+    }
+}
+
+/// Lock screen backend trait.
+///
+/// A backend translates the configuration into a command for an external lock screen tool.
+pub trait Backend {
+    /// The name of the backend, as used in the configuration.
+    fn name(&self) -> &'static str;
+
+    /// The binary that is invoked to show the lock screen.
+    fn binary(&self) -> &'static str;
+
+    /// Check whether this backend is available, by finding it's binary on the `PATH`.
+    fn is_available(&self) -> bool {
+        util::find_binary(self.binary()).is_some()
+    }
+
+    /// Translate the common lock screen `settings` into a command for this tool.
+    fn settings(&self, settings: &Settings) -> LockCommand;
+
+    /// Translate the tool specific `params` from the configuration into a command.
+    ///
+    /// Parameters that are invalid or that the tool doesn't support are left out with a warning,
+    /// so the screen is still locked.
+    fn params(&self, params: &BTreeMap<String, String>) -> LockCommand;
+
+    /// Build the command to show the image at the given `path` as background.
+    fn image(&self, path: &str) -> LockCommand;
+
+    /// Build the command to show a raw image with the given `dimensions` as background,
+    /// that is read from stdin in BGRX format.
+    ///
+    /// If the tool doesn't support raw images, `None` is returned.
+    fn raw_image(&self, _dimensions: (u32, u32)) -> Option<LockCommand> {
+        None
+    }
+}
+
+/// Warn that the tool specific parameter with the given `name` is left out, because of `err`.
+pub fn skip_param(name: &str, err: &Error) {
+    eprintln!("{} warning: ignoring parameter '{}'\n  caused by: {}", app::NAME, name, err);
+}
@@ -0,0 +1,76 @@
+use std::env;
+
+use super::*;
+use super::installed::Variant;
+
+use err::{Error, Result};
+use util;
+
+/// Lock screen backend selector.
+///
+/// This creates the lock screen backend by it's name from the configuration,
+/// or detects which backend to use based on the session and the tools that are available.
+pub struct BackendSelector {}
+
+impl BackendSelector {
+    /// Select the backend with the given `name`, or detect an available backend if `None`.
+    ///
+    /// An error is returned if the name is unknown, or if no usable backend is detected.
+    pub fn select(name: Option<&str>) -> Result<Box<Backend>> {
+        match name {
+            Some(name) => BackendSelector::create_backend_by_name(name),
+            None => BackendSelector::detect(),
+        }
+    }
+
+    /// Create a backend instance by the given backend `name`.
+    ///
+    /// An error is returned if the backend name is unknown.
+    pub fn create_backend_by_name(name: &str) -> Result<Box<Backend>> {
+        match name.trim().to_lowercase().as_str() {
+            "i3lock" => Ok(Box::new(I3lock::new(Variant::I3lock))),
+            "i3lock-color" => Ok(Box::new(I3lock::new(Variant::I3lockColor))),
+            "swaylock" => Ok(Box::new(Swaylock::new())),
+            "swaylock-effects" => Ok(Box::new(Swaylock::effects())),
+            "xsecurelock" => Ok(Box::new(Xsecurelock::new())),
+            _ => Err(Error::config(format!(
+                "Unknown lock backend '{}', use i3lock, i3lock-color, swaylock, swaylock-effects or xsecurelock",
+                name.trim(),
+            ))),
+        }
+    }
+
+    /// Detect the backend to use.
+    ///
+    /// On Wayland `swaylock` is used. Otherwise the installed i3lock variant is used,
+    /// falling back to `xsecurelock` if i3lock isn't installed.
+    ///
+    /// An error is returned on Wayland if `swaylock` isn't installed,
+    /// because the X11 lock screens can't lock a Wayland session.
+    pub fn detect() -> Result<Box<Backend>> {
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            if util::find_binary("swaylock").is_none() {
+                return Err(Error::lock(
+                    "Running on Wayland, but swaylock isn't installed, install swaylock or swaylock-effects"
+                ));
+            }
+            return Ok(Box::new(Swaylock::new()));
+        }
+
+        // Use the installed i3lock variant
+        let i3lock = I3lock::with_installed(Variant::I3lock, None);
+        if i3lock.is_available() {
+            return Ok(match I3lock::probe() {
+                Some(installed) => Box::new(I3lock::with_installed(installed.variant, Some(installed))),
+                None => Box::new(i3lock),
+            });
+        }
+
+        let xsecurelock = Xsecurelock::new();
+        if xsecurelock.is_available() {
+            return Ok(Box::new(xsecurelock));
+        }
+
+        Ok(Box::new(i3lock))
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use app;
+
+use super::{Backend, Installed, LockCommand, Opt, Settings};
+use super::backend::skip_param;
+use super::installed::Variant;
+
+/// Lock screen backend using `i3lock`, or it's fork `i3lock-color`.
+pub struct I3lock {
+    /// The variant this backend was configured for.
+    variant: Variant,
+
+    /// The installed i3lock, or the configured variant if it couldn't be probed.
+    installed: Installed,
+}
+
+impl I3lock {
+    /// Construct the backend for the given i3lock `variant`.
+    ///
+    /// The installed i3lock is probed, to leave out options it doesn't support.
+    pub fn new(variant: Variant) -> I3lock {
+        I3lock::with_installed(variant, I3lock::probe())
+    }
+
+    /// Construct the backend for the given i3lock `variant` and probed `installed` i3lock.
+    ///
+    /// If the installed i3lock is unknown, the configured variant is assumed.
+    pub fn with_installed(variant: Variant, installed: Option<Installed>) -> I3lock {
+        I3lock {
+            variant,
+            installed: installed.unwrap_or_else(|| Installed::assume(variant)),
+        }
+    }
+
+    /// Probe the installed i3lock, and warn if that fails.
+    pub fn probe() -> Option<Installed> {
+        match Installed::probe() {
+            Ok(Some(installed)) => Some(installed),
+            Ok(None) => {
+                eprintln!("{} warning: unable to determine the i3lock version", app::NAME);
+                None
+            },
+            Err(err) => {
+                eprintln!("{} warning: {}", app::NAME, err);
+                None
+            },
+        }
+    }
+
+    /// Check whether the installed i3lock supports the option with the given `name`.
+    fn supports(&self, name: &str) -> bool {
+        self.installed.requirement(Opt::find(name).unwrap()).is_none()
+    }
+}
+
+impl Backend for I3lock {
+    fn name(&self) -> &'static str {
+        self.variant.name()
+    }
+
+    fn binary(&self) -> &'static str {
+        "i3lock"
+    }
+
+    fn settings(&self, settings: &Settings) -> LockCommand {
+        let mut cmd = LockCommand::new();
+
+        if let Some(color) = settings.color {
+            cmd.arg(format!("--color={}", &color.to_hex()[1..7]));
+        }
+        match settings.indicator {
+            Some(true) if self.supports("indicator") => { cmd.arg("--indicator"); },
+            Some(false) => { cmd.arg("--no-unlock-indicator"); },
+            _ => {},
+        }
+        if settings.ignore_empty_password {
+            cmd.arg("--ignore-empty-password");
+        }
+
+        cmd
+    }
+
+    fn params(&self, params: &BTreeMap<String, String>) -> LockCommand {
+        let mut cmd = LockCommand::new();
+
+        // Validate all parameters against the known options, and build their arguments
+        for (arg, val) in params {
+            let (opt, args) = match Opt::find_or_suggest(arg).and_then(|opt| Ok((opt, opt.args(val)?))) {
+                Ok(found) => found,
+                Err(err) => {
+                    skip_param(arg, &err);
+                    continue;
+                },
+            };
+
+            // Skip options the installed i3lock doesn't support
+            match self.installed.requirement(opt) {
+                Some(ref required) if !args.is_empty() => {
+                    eprintln!(
+                        "{} warning: i3lock option '{}' is not supported by {}, requires {}, ignoring it",
+                        app::NAME,
+                        opt.name,
+                        self.installed,
+                        required,
+                    );
+                    continue;
+                },
+                _ => {},
+            }
+
+            for arg in args {
+                cmd.arg(arg);
+            }
+        }
+
+        cmd
+    }
+
+    fn image(&self, path: &str) -> LockCommand {
+        let mut cmd = LockCommand::new();
+        cmd.arg("--image").arg(path);
+        cmd
+    }
+
+    fn raw_image(&self, dimensions: (u32, u32)) -> Option<LockCommand> {
+        // Raw images are only supported since i3lock 2.11
+        if !self.supports("raw") {
+            return None;
+        }
+
+        let mut cmd = LockCommand::new();
+        cmd.arg(format!("--raw={}x{}:bgrx", dimensions.0, dimensions.1))
+            .arg("--image")
+            .arg("/dev/stdin");
+        Some(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use lock::{Backend, Installed, Settings};
+    use lock::installed::Variant;
+
+    use super::I3lock;
+
+    /// Build the arguments for the given i3lock `params`, for the installed `version` output.
+    fn args(version: &str, params: &[(&str, &str)]) -> Vec<String> {
+        let params: BTreeMap<String, String> = params.iter()
+            .map(|&(name, value)| (name.into(), value.into()))
+            .collect();
+        I3lock::with_installed(Variant::I3lockColor, Installed::parse(version))
+            .params(&params)
+            .args
+    }
+
+    #[test]
+    fn params() {
+        let args = args("i3lock: version 2.13.c.4", &[
+            ("c", "#ff000080"),
+            ("ring-color", "white"),
+            ("radius", "120"),
+            ("show-failed-attempts", ""),
+            ("beep", "false"),
+        ]);
+        assert_eq!(args, vec![
+            "--color=ff0000",
+            "--radius=120",
+            "--ring-color=ffffffff",
+            "--show-failed-attempts",
+        ]);
+    }
+
+    #[test]
+    fn params_skip_invalid() {
+        // Invalid and unsupported parameters are left out, so the screen is still locked
+        let args = args("i3lock: version 2.10", &[
+            ("colr", "ff0000"),
+            ("radius", "big"),
+            ("color", "not a color"),
+            ("ring-color", "ffffff"),
+            ("tiling", "maybe"),
+            ("show-failed-attempts", "yes"),
+        ]);
+        assert_eq!(args, vec!["--show-failed-attempts"]);
+    }
+
+    #[test]
+    fn settings_indicator() {
+        let settings = Settings { indicator: Some(true), ..Settings::default() };
+        let settings_args = |version: &str| I3lock::with_installed(Variant::I3lockColor, Installed::parse(version))
+            .settings(&settings)
+            .args;
+
+        // i3lock-color supported the indicator before the option renames, upstream i3lock never did
+        assert_eq!(settings_args("i3lock: version 2.12.c"), vec!["--indicator"]);
+        assert_eq!(settings_args("i3lock: version 2.13.c.4"), vec!["--indicator"]);
+        assert!(settings_args("i3lock: version 2.13").is_empty());
+    }
+}
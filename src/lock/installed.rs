@@ -0,0 +1,264 @@
+extern crate version_compare;
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::process::Command;
+
+use self::version_compare::{CompOp, VersionCompare};
+
+use err::{Error, Result};
+
+use super::Opt;
+
+/// The first i3lock-color version using the current option names.
+///
+/// Older versions used names such as `ringcolor` instead of `ring-color`.
+pub const MIN_COLOR_VERSION: &'static str = "2.13";
+
+/// A variant of i3lock.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+    /// The upstream i3lock.
+    I3lock,
+
+    /// i3lock-color, a fork of i3lock supporting many visual customizations.
+    I3lockColor,
+}
+
+impl Variant {
+    /// Get the name of the variant.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Variant::I3lock => "i3lock",
+            Variant::I3lockColor => "i3lock-color",
+        }
+    }
+}
+
+/// The installed i3lock binary, describing it's variant and version.
+#[derive(Clone, Debug)]
+pub struct Installed {
+    /// The variant of i3lock.
+    pub variant: Variant,
+
+    /// The full version string, such as `2.13` or `2.13.c.4`.
+    pub version: String,
+
+    /// The numeric part of the version, such as `2.13`.
+    pub number: String,
+}
+
+impl Installed {
+    /// Probe the installed i3lock binary by invoking `i3lock --version`.
+    ///
+    /// An error is returned if i3lock could not be invoked,
+    /// `None` is returned if it's output could not be recognized.
+    pub fn probe() -> Result<Option<Installed>> {
+        let out = Command::new("i3lock")
+            .arg("--version")
+            .output()
+            .map_err(|err| Error::lock("Failed to invoke i3lock to determine it's version").caused_by(err))?;
+
+        // i3lock writes it's version to stderr, check both streams to be safe
+        let output = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&out.stderr),
+            String::from_utf8_lossy(&out.stdout),
+        );
+
+        Ok(Installed::parse(&output))
+    }
+
+    /// Assume the given i3lock `variant` is installed, when it's version is unknown.
+    pub fn assume(variant: Variant) -> Installed {
+        Installed {
+            variant,
+            version: String::new(),
+            number: String::new(),
+        }
+    }
+
+    /// Parse the output of `i3lock --version`.
+    ///
+    /// i3lock reports a version such as `2.13`,
+    /// i3lock-color adds a `c` to it such as `2.13.c.4` or `2.11-c`.
+    ///
+    /// If the output could not be recognized, `None` is returned.
+    pub fn parse(output: &str) -> Option<Installed> {
+        // Find the version following the version keyword
+        let version = output.split_whitespace()
+            .skip_while(|word| !word.eq_ignore_ascii_case("version"))
+            .nth(1)?
+            .trim_end_matches(',');
+
+        // Split the numeric part from the variant suffix
+        let number: String = version.chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        let number = number.trim_end_matches('.');
+        if number.is_empty() {
+            return None;
+        }
+        let suffix = version[number.len()..].to_lowercase();
+
+        let variant = if suffix.starts_with(".c") || suffix.starts_with("-c") || output.contains("i3lock-color") {
+            Variant::I3lockColor
+        } else {
+            Variant::I3lock
+        };
+
+        Some(Installed {
+            variant,
+            version: version.into(),
+            number: number.into(),
+        })
+    }
+
+    /// Check whether the installed version is at least the given `version`.
+    ///
+    /// If the installed version is unknown or could not be compared, `true` is assumed.
+    pub fn is_at_least(&self, version: &str) -> bool {
+        if self.number.is_empty() {
+            return true;
+        }
+
+        VersionCompare::compare_to(&self.number, version, &CompOp::Ge).unwrap_or(true)
+    }
+
+    /// Describe the i3lock variant and version required for the given `opt`,
+    /// such as `i3lock-color 2.13 or newer`.
+    ///
+    /// If the installed i3lock supports the option, `None` is returned.
+    pub fn requirement(&self, opt: &Opt) -> Option<String> {
+        let variant = if opt.color_only { Variant::I3lockColor } else { Variant::I3lock };
+        let other_variant = opt.color_only && self.variant != Variant::I3lockColor;
+        let too_old = opt.since.map(|since| !self.is_at_least(since)).unwrap_or(false);
+        if !other_variant && !too_old {
+            return None;
+        }
+
+        Some(match opt.since {
+            Some(since) => format!("{} {} or newer", variant.name(), since),
+            None => variant.name().into(),
+        })
+    }
+}
+
+impl Display for Installed {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.version.is_empty() {
+            write!(f, "{}", self.variant.name())
+        } else {
+            write!(f, "{} {}", self.variant.name(), self.version)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lock::Opt;
+
+    use super::{Installed, Variant, MIN_COLOR_VERSION};
+
+    /// Parse the given `i3lock --version` output, which must be recognized.
+    fn parse(output: &str) -> Installed {
+        Installed::parse(output).expect("version output not recognized")
+    }
+
+    #[test]
+    fn parse_i3lock() {
+        let installed = parse("i3lock: version 2.13 © 2010 Michael Stapelberg\n");
+        assert_eq!(installed.variant, Variant::I3lock);
+        assert_eq!(installed.version, "2.13");
+        assert_eq!(installed.number, "2.13");
+
+        let installed = parse("i3lock: version 2.11.1 © 2010 Michael Stapelberg\n");
+        assert_eq!(installed.variant, Variant::I3lock);
+        assert_eq!(installed.number, "2.11.1");
+    }
+
+    #[test]
+    fn parse_i3lock_color() {
+        let installed = parse("i3lock: version 2.13.c.4 © 2010 Michael Stapelberg, © 2015 Cassandra Fox, © 2021 Raymond Li\n");
+        assert_eq!(installed.variant, Variant::I3lockColor);
+        assert_eq!(installed.version, "2.13.c.4");
+        assert_eq!(installed.number, "2.13");
+
+        let installed = parse("i3lock: version 2.11-c (2018-05-28), © 2010 Michael Stapelberg\n");
+        assert_eq!(installed.variant, Variant::I3lockColor);
+        assert_eq!(installed.version, "2.11-c");
+        assert_eq!(installed.number, "2.11");
+    }
+
+    #[test]
+    fn parse_distro_builds() {
+        // Builds from git report the commits since the last release
+        let installed = parse("i3lock: version 2.12-7-g4dd2e80 © 2010 Michael Stapelberg\n");
+        assert_eq!(installed.variant, Variant::I3lock);
+        assert_eq!(installed.number, "2.12");
+
+        let installed = parse("i3lock: version 2.13.c.5-2-gfc6b8b9 © 2010 Michael Stapelberg\n");
+        assert_eq!(installed.variant, Variant::I3lockColor);
+        assert_eq!(installed.number, "2.13");
+
+        // Packages that report the fork by name, on stdout after an empty stderr
+        let installed = parse("\ni3lock-color: version 2.12, © 2010 Michael Stapelberg\n");
+        assert_eq!(installed.variant, Variant::I3lockColor);
+        assert_eq!(installed.version, "2.12");
+    }
+
+    #[test]
+    fn parse_unrecognized() {
+        assert!(Installed::parse("").is_none());
+        assert!(Installed::parse("i3lock: unrecognized option '--version'\n").is_none());
+        assert!(Installed::parse("i3lock: version\n").is_none());
+        assert!(Installed::parse("i3lock: version unknown\n").is_none());
+    }
+
+    #[test]
+    fn requirement() {
+        let color = Opt::find("ring-color").unwrap();
+        let raw = Opt::find("raw").unwrap();
+        let beep = Opt::find("beep").unwrap();
+
+        // Up to date i3lock-color supports everything
+        let installed = parse("i3lock: version 2.13.c.4 © 2010 Michael Stapelberg");
+        assert_eq!(installed.requirement(color), None);
+        assert_eq!(installed.requirement(raw), None);
+        assert_eq!(installed.requirement(beep), None);
+
+        // i3lock-color before the option renames lacks the renamed options,
+        // but supports the options that kept their name
+        let required = Some(format!("i3lock-color {} or newer", MIN_COLOR_VERSION));
+        let installed = parse("i3lock: version 2.12.c © 2010 Michael Stapelberg");
+        assert_eq!(installed.requirement(color), required);
+        assert_eq!(installed.requirement(raw), None);
+        for name in &["clock", "indicator", "radius", "ring-width", "blur", "pass-media-keys"] {
+            assert_eq!(installed.requirement(Opt::find(name).unwrap()), None, "{}", name);
+        }
+
+        // Older i3lock-color lacks options added later
+        let installed = parse("i3lock: version 2.10-c © 2010 Michael Stapelberg");
+        assert_eq!(installed.requirement(Opt::find("clock").unwrap()), None);
+        assert_eq!(
+            installed.requirement(Opt::find("pass-media-keys").unwrap()),
+            Some("i3lock-color 2.11 or newer".into()),
+        );
+
+        // Upstream i3lock never supports color options, and only supports raw images since 2.11
+        let installed = parse("i3lock: version 2.10 © 2010 Michael Stapelberg");
+        assert_eq!(installed.requirement(color), required);
+        assert_eq!(installed.requirement(Opt::find("clock").unwrap()), Some("i3lock-color".into()));
+        assert_eq!(installed.requirement(raw), Some("i3lock 2.11 or newer".into()));
+        assert_eq!(installed.requirement(beep), None);
+    }
+
+    #[test]
+    fn requirement_unknown_version() {
+        let installed = Installed::assume(Variant::I3lockColor);
+        assert_eq!(installed.requirement(Opt::find("ring-color").unwrap()), None);
+        assert_eq!(installed.requirement(Opt::find("raw").unwrap()), None);
+
+        let installed = Installed::assume(Variant::I3lock);
+        assert!(installed.requirement(Opt::find("ring-color").unwrap()).is_some());
+    }
+}
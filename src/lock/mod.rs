@@ -0,0 +1,18 @@
+pub mod backend;
+pub mod backend_selector;
+pub mod i3lock;
+pub mod installed;
+pub mod opt;
+pub mod settings;
+pub mod swaylock;
+pub mod xsecurelock;
+
+// Reexport modules
+pub use self::backend::{Backend, LockCommand};
+pub use self::backend_selector::BackendSelector;
+pub use self::i3lock::I3lock;
+pub use self::installed::Installed;
+pub use self::opt::Opt;
+pub use self::settings::Settings;
+pub use self::swaylock::Swaylock;
+pub use self::xsecurelock::Xsecurelock;
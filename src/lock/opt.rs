@@ -0,0 +1,263 @@
+use err::{Error, Result};
+use img::Color;
+use util;
+
+use super::installed::MIN_COLOR_VERSION;
+
+/// The kind of value an option takes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OptKind {
+    /// A flag without a value, that may be enabled or disabled.
+    Flag,
+
+    /// A color, passed as `rrggbb`.
+    Rgb,
+
+    /// A color with alpha channel, passed as `rrggbbaa`.
+    Rgba,
+
+    /// An integer.
+    Int,
+
+    /// A number, that may be fractional.
+    Float,
+
+    /// Any text, such as a path, a font name or a time format.
+    Text,
+}
+
+/// An i3lock option.
+pub struct Opt {
+    /// The long option name, without hyphens.
+    pub name: &'static str,
+
+    /// The short option name, if there is any.
+    pub short: Option<char>,
+
+    /// The kind of value the option takes.
+    pub kind: OptKind,
+
+    /// Whether the option is only supported by i3lock-color, and not by i3lock itself.
+    pub color_only: bool,
+
+    /// The first version supporting the option by this name, if it wasn't always supported.
+    ///
+    /// This is an i3lock-color version for options only supported by i3lock-color.
+    pub since: Option<&'static str>,
+}
+
+/// Shorthand for defining an option supported by both i3lock and i3lock-color.
+const fn opt(name: &'static str, short: Option<char>, kind: OptKind) -> Opt {
+    Opt { name, short, kind, color_only: false, since: None }
+}
+
+/// Shorthand for defining an option only supported by i3lock-color,
+/// by this name since the option renames in `MIN_COLOR_VERSION`.
+const fn color(name: &'static str, kind: OptKind) -> Opt {
+    color_since(name, kind, Some(MIN_COLOR_VERSION))
+}
+
+/// Shorthand for defining an option only supported by i3lock-color, since the given version.
+const fn color_since(name: &'static str, kind: OptKind, since: Option<&'static str>) -> Opt {
+    Opt { name, short: None, kind, color_only: true, since }
+}
+
+/// All known i3lock and i3lock-color options.
+pub static OPTS: &'static [Opt] = &[
+    // Options supported by i3lock
+    opt("beep", Some('b'), OptKind::Flag),
+    opt("color", Some('c'), OptKind::Rgb),
+    opt("debug", None, OptKind::Flag),
+    opt("ignore-empty-password", Some('e'), OptKind::Flag),
+    opt("image", Some('i'), OptKind::Text),
+    opt("nofork", Some('n'), OptKind::Flag),
+    opt("no-unlock-indicator", Some('u'), OptKind::Flag),
+    opt("pointer", Some('p'), OptKind::Text),
+    Opt { name: "raw", short: None, kind: OptKind::Text, color_only: false, since: Some("2.11") },
+    opt("show-failed-attempts", Some('f'), OptKind::Flag),
+    opt("show-keyboard-layout", None, OptKind::Flag),
+    opt("tiling", Some('t'), OptKind::Flag),
+
+    // Colors
+    color("inside-color", OptKind::Rgba),
+    color("insidever-color", OptKind::Rgba),
+    color("insidewrong-color", OptKind::Rgba),
+    color("insideclear-color", OptKind::Rgba),
+    color("ring-color", OptKind::Rgba),
+    color("ringver-color", OptKind::Rgba),
+    color("ringwrong-color", OptKind::Rgba),
+    color("ringclear-color", OptKind::Rgba),
+    color("line-color", OptKind::Rgba),
+    color("keyhl-color", OptKind::Rgba),
+    color("bshl-color", OptKind::Rgba),
+    color("separator-color", OptKind::Rgba),
+    color("verif-color", OptKind::Rgba),
+    color("wrong-color", OptKind::Rgba),
+    color("modif-color", OptKind::Rgba),
+    color("layout-color", OptKind::Rgba),
+    color("time-color", OptKind::Rgba),
+    color("date-color", OptKind::Rgba),
+    color("greeter-color", OptKind::Rgba),
+    color("time-outline-color", OptKind::Rgba),
+    color("date-outline-color", OptKind::Rgba),
+    color("layout-outline-color", OptKind::Rgba),
+    color("verif-outline-color", OptKind::Rgba),
+    color("wrong-outline-color", OptKind::Rgba),
+    color("greeter-outline-color", OptKind::Rgba),
+    color("modif-outline-color", OptKind::Rgba),
+
+    // Ring and indicator
+    color_since("indicator", OptKind::Flag, None),
+    color_since("radius", OptKind::Float, None),
+    color_since("ring-width", OptKind::Float, None),
+    color_since("line-uses-inside", OptKind::Flag, None),
+    color_since("line-uses-ring", OptKind::Flag, None),
+    color("ind-pos", OptKind::Text),
+
+    // Clock
+    color_since("clock", OptKind::Flag, None),
+    color_since("force-clock", OptKind::Flag, None),
+    color("time-str", OptKind::Text),
+    color("date-str", OptKind::Text),
+    color("time-pos", OptKind::Text),
+    color("date-pos", OptKind::Text),
+    color("time-align", OptKind::Int),
+    color("date-align", OptKind::Int),
+    color_since("refresh-rate", OptKind::Float, None),
+
+    // Texts
+    color("verif-text", OptKind::Text),
+    color("wrong-text", OptKind::Text),
+    color("noinput-text", OptKind::Text),
+    color("lock-text", OptKind::Text),
+    color("lockfailed-text", OptKind::Text),
+    color("greeter-text", OptKind::Text),
+    color("verif-pos", OptKind::Text),
+    color("wrong-pos", OptKind::Text),
+    color("layout-pos", OptKind::Text),
+    color("greeter-pos", OptKind::Text),
+    color_since("keylayout", OptKind::Int, None),
+
+    // Fonts
+    color("time-font", OptKind::Text),
+    color("date-font", OptKind::Text),
+    color("layout-font", OptKind::Text),
+    color("verif-font", OptKind::Text),
+    color("wrong-font", OptKind::Text),
+    color("greeter-font", OptKind::Text),
+    color("time-size", OptKind::Float),
+    color("date-size", OptKind::Float),
+    color("layout-size", OptKind::Float),
+    color("verif-size", OptKind::Float),
+    color("wrong-size", OptKind::Float),
+    color("greeter-size", OptKind::Float),
+    color("modif-size", OptKind::Float),
+
+    // Other
+    color_since("blur", OptKind::Float, None),
+    color_since("screen", OptKind::Int, None),
+    color("no-verify", OptKind::Flag),
+    color_since("pass-media-keys", OptKind::Flag, Some("2.11")),
+    color_since("pass-screen-keys", OptKind::Flag, Some("2.11")),
+    color_since("pass-power-keys", OptKind::Flag, Some("2.11")),
+    color_since("pass-volume-keys", OptKind::Flag, Some("2.11")),
+];
+
+/// Maximum number of suggestions for an unknown option.
+const MAX_SUGGESTIONS: usize = 3;
+
+impl Opt {
+    /// Find the option with the given `name`.
+    ///
+    /// The name may be a long or short option name, and may be prefixed with hyphens.
+    pub fn find(name: &str) -> Option<&'static Opt> {
+        let name = name.trim().trim_start_matches('-');
+
+        OPTS.iter().find(|opt| opt.name == name || (
+            name.chars().count() == 1 && opt.short.map(|short| name.starts_with(short)).unwrap_or(false)
+        ))
+    }
+
+    /// Find the option with the given `name` like `find`.
+    ///
+    /// If the option is unknown, an error is returned suggesting options with a similar name.
+    pub fn find_or_suggest(name: &str) -> Result<&'static Opt> {
+        if let Some(opt) = Opt::find(name) {
+            return Ok(opt);
+        }
+
+        // Suggest the closest matching options
+        let suggestions = Opt::suggest(name);
+        if suggestions.is_empty() {
+            Err(Error::config(format!("Unknown i3lock option '{}'", name)))
+        } else {
+            Err(Error::config(format!(
+                "Unknown i3lock option '{}', did you mean: {}?",
+                name,
+                suggestions.join(", "),
+            )))
+        }
+    }
+
+    /// Get the names of options that are similar to the given unknown `name`,
+    /// with the closest match first.
+    pub fn suggest(name: &str) -> Vec<&'static str> {
+        let name = name.trim().trim_start_matches('-').to_lowercase();
+        let max_distance = (name.len() / 4).max(1);
+
+        let mut matches: Vec<(usize, &'static str)> = OPTS.iter()
+            .map(|opt| (util::levenshtein(&name, opt.name), opt.name))
+            .filter(|&(distance, _)| distance <= max_distance)
+            .collect();
+        matches.sort();
+
+        matches.into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// Build the command line arguments for this option with the given `value`.
+    ///
+    /// The value is validated and normalized for the kind of option, colors are converted
+    /// into the hexadecimal format i3lock expects. A flag is enabled with an empty value or
+    /// `true`, and is left out when `false`.
+    ///
+    /// An error is returned if the value is invalid.
+    pub fn args(&self, value: &str) -> Result<Vec<String>> {
+        let value = value.trim();
+        let invalid = |expected: &str| Error::config(format!(
+            "Invalid value '{}' for i3lock option '{}', expected {}",
+            value,
+            self.name,
+            expected,
+        ));
+
+        if value.is_empty() && self.kind != OptKind::Flag {
+            return Err(invalid("a value"));
+        }
+
+        let value = match self.kind {
+            OptKind::Flag => return match value.to_lowercase().as_str() {
+                "" | "true" | "yes" | "on" => Ok(vec![format!("--{}", self.name)]),
+                "false" | "no" | "off" => Ok(vec![]),
+                _ => Err(invalid("no value, true or false")),
+            },
+            OptKind::Rgb | OptKind::Rgba => {
+                let hex = Color::parse(value)
+                    .map_err(|err| invalid("a color").caused_by(err))?
+                    .to_hex();
+                if self.kind == OptKind::Rgb { hex[1..7].to_string() } else { hex[1..].to_string() }
+            },
+            OptKind::Int => value.parse::<i64>()
+                .map_err(|err| invalid("an integer").caused_by(err))?
+                .to_string(),
+            OptKind::Float => value.parse::<f64>()
+                .map_err(|err| invalid("a number").caused_by(err))?
+                .to_string(),
+            OptKind::Text => value.to_string(),
+        };
+
+        Ok(vec![format!("--{}={}", self.name, value)])
+    }
+}
@@ -0,0 +1,50 @@
+use app;
+use cmd;
+use config::Config;
+use img::Color;
+
+/// Lock screen settings that are common to all backends.
+///
+/// Each backend translates these into the flags of it's tool,
+/// so the same configuration works with every lock screen.
+#[derive(Clone, Debug, Default)]
+pub struct Settings {
+    /// The background color, shown where the image doesn't cover the screen.
+    pub color: Option<Color>,
+
+    /// Whether to always show the unlock indicator, or to never show it.
+    ///
+    /// If `None`, the default behaviour of the tool is used.
+    pub indicator: Option<bool>,
+
+    /// Whether to ignore submitting an empty password.
+    pub ignore_empty_password: bool,
+}
+
+impl Settings {
+    /// Load the settings from the given configuration.
+    ///
+    /// Invalid settings are left out with a warning, so the screen is still locked.
+    pub fn from(config: &Config) -> Self {
+        let color = config.get(cmd::ARG_LOCK_COLOR)
+            .and_then(|color| color.as_str())
+            .and_then(|color| match Color::parse(color) {
+                Ok(color) => Some(color),
+                Err(err) => {
+                    eprintln!(
+                        "{} warning: ignoring invalid lock screen color '{}'\n  caused by: {}",
+                        app::NAME,
+                        color,
+                        err,
+                    );
+                    None
+                },
+            });
+
+        Settings {
+            color,
+            indicator: config.get_bool(cmd::ARG_LOCK_INDICATOR),
+            ignore_empty_password: config.get_bool(cmd::ARG_LOCK_IGNORE_EMPTY_PASSWORD).unwrap_or(false),
+        }
+    }
+}
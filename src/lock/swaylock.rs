@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use err::Error;
+use img::Color;
+
+use super::{Backend, LockCommand, Settings};
+use super::backend::skip_param;
+
+/// Lock screen backend using `swaylock`, or it's fork `swaylock-effects`, for Wayland compositors.
+pub struct Swaylock {
+    /// Whether the swaylock-effects options are supported.
+    effects: bool,
+}
+
+impl Swaylock {
+    /// Construct the backend for `swaylock`.
+    pub fn new() -> Swaylock {
+        Swaylock { effects: false }
+    }
+
+    /// Construct the backend for `swaylock-effects`.
+    pub fn effects() -> Swaylock {
+        Swaylock { effects: true }
+    }
+}
+
+impl Backend for Swaylock {
+    fn name(&self) -> &'static str {
+        if self.effects { "swaylock-effects" } else { "swaylock" }
+    }
+
+    fn binary(&self) -> &'static str {
+        "swaylock"
+    }
+
+    fn settings(&self, settings: &Settings) -> LockCommand {
+        let mut cmd = LockCommand::new();
+
+        if let Some(color) = settings.color {
+            cmd.arg(format!("--color={}", &color.to_hex()[1..]));
+        }
+        match settings.indicator {
+            Some(true) if self.effects => { cmd.arg("--indicator"); },
+            Some(false) => { cmd.arg("--no-unlock-indicator"); },
+            _ => {},
+        }
+        if settings.ignore_empty_password {
+            cmd.arg("--ignore-empty-password");
+        }
+
+        cmd
+    }
+
+    /// Parameters are passed to swaylock as is, because it's options differ between versions.
+    /// Values of color options may be given in any color format, like for i3lock.
+    fn params(&self, params: &BTreeMap<String, String>) -> LockCommand {
+        let mut cmd = LockCommand::new();
+
+        for (arg, val) in params {
+            let name = arg.trim().trim_start_matches('-');
+            let val = val.trim();
+
+            // A flag is enabled with an empty value or true, and is left out when false
+            match val.to_lowercase().as_str() {
+                _ if name.is_empty() => skip_param(arg, &Error::config("Empty swaylock option name")),
+                "" | "true" | "yes" | "on" => { cmd.arg(format!("--{}", name)); },
+                "false" | "no" | "off" => {},
+                _ if name.ends_with("color") => match Color::parse(val) {
+                    Ok(color) => { cmd.arg(format!("--{}={}", name, &color.to_hex()[1..])); },
+                    Err(err) => skip_param(arg, &Error::config(format!(
+                        "Invalid value '{}' for swaylock option '{}', expected a color",
+                        val,
+                        name,
+                    )).caused_by(err)),
+                },
+                _ => { cmd.arg(format!("--{}={}", name, val)); },
+            }
+        }
+
+        cmd
+    }
+
+    fn image(&self, path: &str) -> LockCommand {
+        let mut cmd = LockCommand::new();
+        cmd.arg("--image").arg(path);
+        cmd
+    }
+}
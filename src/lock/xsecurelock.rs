@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use app;
+use err::Error;
+use shell;
+
+use super::{Backend, LockCommand, Settings};
+use super::backend::skip_param;
+
+/// Prefix of the environment variables xsecurelock is configured with.
+const ENV_PREFIX: &'static str = "XSECURELOCK_";
+
+/// Lock screen backend using `xsecurelock`.
+///
+/// xsecurelock is configured through environment variables.
+/// The image is shown through it's `saver_mpv` screen saver, which requires `mpv`.
+pub struct Xsecurelock {}
+
+impl Xsecurelock {
+    pub fn new() -> Xsecurelock {
+        Xsecurelock {}
+    }
+}
+
+impl Backend for Xsecurelock {
+    fn name(&self) -> &'static str {
+        "xsecurelock"
+    }
+
+    fn binary(&self) -> &'static str {
+        "xsecurelock"
+    }
+
+    fn settings(&self, settings: &Settings) -> LockCommand {
+        let mut cmd = LockCommand::new();
+
+        if let Some(color) = settings.color {
+            cmd.env("XSECURELOCK_BACKGROUND_COLOR", &color.to_hex()[..7]);
+        }
+        if settings.indicator == Some(false) {
+            cmd.env("XSECURELOCK_PASSWORD_PROMPT", "hidden");
+        }
+        if settings.ignore_empty_password {
+            eprintln!("{} warning: xsecurelock doesn't support ignoring empty passwords", app::NAME);
+        }
+
+        cmd
+    }
+
+    /// Parameters are set as environment variables,
+    /// named like `XSECURELOCK_SAVER` or `saver` for short.
+    fn params(&self, params: &BTreeMap<String, String>) -> LockCommand {
+        let mut cmd = LockCommand::new();
+
+        for (arg, val) in params {
+            let mut name = arg.trim().trim_start_matches('-').replace('-', "_").to_uppercase();
+            if name.is_empty() {
+                skip_param(arg, &Error::config("Empty xsecurelock variable name"));
+                continue;
+            }
+            if !name.starts_with(ENV_PREFIX) {
+                name.insert_str(0, ENV_PREFIX);
+            }
+
+            cmd.env(name, val.trim());
+        }
+
+        cmd
+    }
+
+    fn image(&self, path: &str) -> LockCommand {
+        let mut cmd = LockCommand::new();
+        cmd.env("XSECURELOCK_SAVER", "saver_mpv")
+            .env("XSECURELOCK_LIST_VIDEOS_COMMAND", format!("echo {}", shell::quote(path)));
+        cmd
+    }
+}
@@ -3,68 +3,195 @@ extern crate clap;
 extern crate lazy_static;
 extern crate shellexpand;
 extern crate tempdir;
+extern crate yaml_rust;
 
 mod app;
 mod cmd;
 mod config;
+mod config_migrate;
+mod config_paths;
+mod config_schema;
 mod err;
 mod img;
 mod intent;
+mod lock;
+mod monitor;
+mod screenshot;
+mod shell;
+mod util;
 mod yaml_helper;
+mod yaml_marked;
 
-use std::env;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
-use std::process::{Command, exit};
+use std::process::exit;
 
-use clap::{Arg, ArgMatches, App};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use tempdir::TempDir;
+use yaml_rust::Yaml;
 
 use config::Config;
+use config_paths::{ConfigPaths, Source, ENV_CONFIG};
 use err::{Error, Result};
-use img::img_proc::{ImgProc, ImgProcParser};
+use img::ImgEdit;
+use img::img_proc::{ImgProc, ImgProcParser, Property};
 use intent::Intent;
+use monitor::Monitor;
+use screenshot::BackendSelector;
+use yaml_helper::YamlHelper;
 
 /// Main application entry point.
 fn main() {
-    // TODO: Remove this after testing
-    // Image processor parser test
-    ImgProcParser::parse("blur:sigma=3".into()).unwrap();
+    // Parse arguments
+    let matches = parse_args();
+
+    // List the available filters, which doesn't need the configuration
+    if matches.subcommand_matches(cmd::CMD_FILTERS).is_some() {
+        list_filters();
+        return;
+    }
+
+    // Find the configuration files to load
+    let paths = ConfigPaths::find(&matches);
+
+    // Show the configuration files
+    if let Some(matches) = matches.subcommand_matches(cmd::CMD_CONFIG) {
+        if matches.subcommand_matches(cmd::CMD_CONFIG_PATHS).is_some() {
+            list_config_paths(&paths);
+        }
+        if let Some(matches) = matches.subcommand_matches(cmd::CMD_CONFIG_MIGRATE) {
+            if let Err(err) = migrate_configs(&paths, matches.is_present(cmd::ARG_DRY)) {
+                quit(err);
+            }
+        }
+        return;
+    }
 
-    // Create a configuration instance
+    // Merge the configuration files, and the arguments
     let mut config = Config::default();
+    if let Err(err) = load_config(&mut config, &paths) {
+        quit(err);
+    }
+    if let Err(err) = config.parse_matches(&matches) {
+        quit(err);
+    }
 
-    // List of paths to check for dotfiles at
-    let paths = vec![
-        "~/.config/i3lock-slick/config.yml",
-        "~/.i3lock-slick.yml",
-    ];
+    // Render the lock image to a file, or show the lock screen
+    let result = match config.get(cmd::ARG_OUTPUT).and_then(|path| path.as_str()).map(|path| path.to_owned()) {
+        Some(path) => render_output(&path, &config),
+        None => lock(&mut config),
+    };
+    if let Err(err) = result {
+        quit(err);
+    }
+}
 
-    // Merge existing dotfiles
-    for path in paths {
-        // Expand the path
-        let path = shellexpand::full_with_context_no_errors(
-            &path,
-            || env::home_dir(),
-            |var| env::var(var).ok(),
-        ).to_string();
-
-        // Get the path, load dotfile if it exists
-        let path = Path::new(&path);
+/// Merge the configuration files at the given `paths` that exist into the `config`.
+///
+/// An error is returned if loading a file failed,
+/// or if an explicitly given file doesn't exist.
+fn load_config(config: &mut Config, paths: &ConfigPaths) -> Result<()> {
+    for path in &paths.paths {
         if path.is_file() {
             println!("Load settings: {:?}", path);
-            config.merge_file(path).unwrap();
+            config.merge_file(path)?;
+        } else if paths.is_explicit() {
+            return Err(Error::config(format!("Configuration file {:?} doesn't exist", path)));
         }
     }
 
-    // Parse arguments
-    config.parse_matches(&parse_args()).expect("Failed to parse CLI arguments");
+    Ok(())
+}
+
+/// Print the configuration files that are loaded, in order.
+fn list_config_paths(paths: &ConfigPaths) {
+    println!("{}", match paths.source {
+        Source::Argument => "Configuration files given with --config:".to_string(),
+        Source::Environment => format!("Configuration files given in ${}:", ENV_CONFIG),
+        Source::Default => "Configuration files, later files override earlier ones:".to_string(),
+        Source::Disabled => "Loading configuration files is disabled with --no-config".to_string(),
+    });
+
+    for path in &paths.paths {
+        println!(
+            "    {} ({})",
+            path.display(),
+            if path.is_file() { "found" } else { "not found" },
+        );
+    }
+}
+
+/// Upgrade the configuration files at the given `paths` to the current version,
+/// see `config_migrate::upgrade`.
+///
+/// The original of each upgraded file is kept with a `.bak` suffix.
+/// If `dry` is true, the upgraded configurations are printed instead.
+fn migrate_configs(paths: &ConfigPaths, dry: bool) -> Result<()> {
+    let paths: Vec<&PathBuf> = paths.paths.iter().filter(|path| path.is_file()).collect();
+    if paths.is_empty() {
+        println!("No configuration files found, see '{} {} {}'", app::NAME, cmd::CMD_CONFIG, cmd::CMD_CONFIG_PATHS);
+    }
+
+    for path in paths {
+        let source = fs::read_to_string(path)
+            .map_err(|err| Error::config(format!("Failed to read configuration file {:?}", path)).caused_by(err))?;
+        let upgraded = config_migrate::upgrade(&source)
+            .map_err(|err| Error::config(format!("Failed to migrate configuration file {:?}", path)).caused_by(err))?;
+        let upgraded = match upgraded {
+            Some(upgraded) => upgraded,
+            None => {
+                println!("{}: up to date", path.display());
+                continue;
+            },
+        };
+
+        // Describe the changes
+        println!("{}:", path.display());
+        for migration in upgraded.migrations {
+            println!("    - {}", migration);
+        }
+        println!("    - Set the version to {}", app::VERSION);
+        if !upgraded.problems.is_empty() {
+            eprintln!(
+                "{} warning: configuration file {:?} has problems, \
+                unknown keys are kept and invalid values are left out:\n  - {}",
+                app::NAME,
+                path,
+                upgraded.problems.join("\n  - "),
+            );
+        }
+        if dry {
+            println!("\n{}", upgraded.source);
+            continue;
+        }
 
-    // Show the lock screen
-    let result = lock(&mut config);
-    if result.is_err() {
-        eprintln!("{}\n{} will now quit", result.unwrap_err(), app::NAME);
-        exit(1);
+        // Keep the original, and write the upgraded configuration
+        let mut backup = path.clone().into_os_string();
+        backup.push(".bak");
+        let backup = PathBuf::from(backup);
+        eprintln!(
+            "{} warning: comments in {:?} are not preserved, the original is kept at {:?}",
+            app::NAME,
+            path,
+            backup,
+        );
+        fs::copy(path, &backup)
+            .map_err(|err| Error::config(format!("Failed to back up configuration file to {:?}", backup)).caused_by(err))?;
+        fs::write(path, upgraded.source)
+            .map_err(|err| Error::config(format!("Failed to write configuration file {:?}", path)).caused_by(err))?;
+        println!("    Upgraded, the original is kept at {}", backup.display());
     }
+
+    Ok(())
+}
+
+/// Print the given `err` with it's causes, and quit with the exit code for the error category.
+fn quit(err: Error) -> ! {
+    eprintln!("{} error: {}\n{} will now quit", app::NAME, err, app::NAME);
+    exit(err.exit_code());
 }
 
 /// Parse all given arguments.
@@ -75,17 +202,57 @@ fn parse_args<'a>() -> ArgMatches<'a> {
         .version(app::VERSION)
         .about(app::DESCRIPTION)
         .author(app::AUTHOR)
+        .arg(Arg::with_name(cmd::ARG_CONFIG)
+            .short("c")
+            .long(cmd::ARG_CONFIG)
+            .value_name("PATH")
+            .help("Load a configuration file instead of the default dotfiles, may be given multiple times")
+            .multiple(true)
+            .number_of_values(1)
+            .takes_value(true))
+        .arg(Arg::with_name(cmd::ARG_NO_CONFIG)
+            .long(cmd::ARG_NO_CONFIG)
+            .help("Don't load any configuration files")
+            .conflicts_with(cmd::ARG_CONFIG))
         .arg(Arg::with_name(cmd::ARG_PARAM)
             .short("p")
             .long(cmd::ARG_PARAM)
             .value_name("ARGUMENT | ARGUMENT=VALUE")
-            .help("Pass an argument to i3lock")
+            .help("Pass an argument to the lock screen")
             .multiple(true)
             .takes_value(true))
         .arg(Arg::with_name(cmd::ARG_DRY)
             .short("d")
             .long(cmd::ARG_DRY)
-            .help("Don't invoke i3lock, output the command to stdout instead"))
+            .help("Don't lock, output the lock screen command to stdout instead"))
+        .arg(Arg::with_name(cmd::ARG_IMAGE)
+            .short("i")
+            .long(cmd::ARG_IMAGE)
+            .value_name("PATH")
+            .help("Use an image or a random image from a directory instead of a screenshot")
+            .takes_value(true))
+        .arg(Arg::with_name(cmd::ARG_OUTPUT)
+            .short("o")
+            .long(cmd::ARG_OUTPUT)
+            .value_name("PATH")
+            .help("Don't lock, save the processed image to the given path instead")
+            .takes_value(true))
+        .arg(Arg::with_name(cmd::ARG_SCRIPT)
+            .short("s")
+            .long(cmd::ARG_SCRIPT)
+            .value_name("PATH")
+            .help("Don't lock, save a standalone shell script that shows the lock screen instead")
+            .takes_value(true))
+        .arg(Arg::with_name(cmd::ARG_RAW)
+            .short("r")
+            .long(cmd::ARG_RAW)
+            .help("Pipe the raw image to the lock screen instead of saving it to a temporary file"))
+        .arg(Arg::with_name(cmd::ARG_PRESET)
+            .short("P")
+            .long(cmd::ARG_PRESET)
+            .value_name("NAME")
+            .help("Apply a preset from the dotfile")
+            .takes_value(true))
         .arg(Arg::with_name(cmd::ARG_FILTER)
             .short("f")
             .long(cmd::ARG_FILTER)
@@ -93,104 +260,350 @@ fn parse_args<'a>() -> ArgMatches<'a> {
             .help("Filter to apply to the image")
             .multiple(true)
             .takes_value(true))
+        .subcommand(SubCommand::with_name(cmd::CMD_CONFIG)
+            .about("Inspect or upgrade the configuration files")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(SubCommand::with_name(cmd::CMD_CONFIG_MIGRATE)
+                .about("Upgrade deprecated settings in the configuration files to the current version")
+                .arg(Arg::with_name(cmd::ARG_DRY)
+                    .short("d")
+                    .long(cmd::ARG_DRY)
+                    .help("Print the upgraded configurations instead of writing them")))
+            .subcommand(SubCommand::with_name(cmd::CMD_CONFIG_PATHS)
+                .about("List the configuration files that are loaded, in order")))
+        .subcommand(SubCommand::with_name(cmd::CMD_FILTERS)
+            .about("List all available filters and their properties"))
         .get_matches()
 }
 
+/// Print a list of all available filters, along with their properties.
+fn list_filters() {
+    for name in ImgProcParser::filter_names() {
+        println!("{}", name);
+
+        // List the properties in alphabetical order
+        let filter = ImgProcParser::create_filter_by_name(name).unwrap();
+        let mut properties: Vec<(&&str, &Property)> = filter.properties().iter().collect();
+        properties.sort_by_key(|property| *property.0);
+
+        for (name, property) in properties {
+            // Describe the default value or whether it is required, and the bounds
+            let prop = &property.prop;
+            let mut details = vec![match prop.as_str() {
+                Some(ref value) if !prop.is_empty() => format!("default: {}", value),
+                _ if property.required => "required".into(),
+                _ => "optional".into(),
+            }];
+            details.extend(property.bounds());
+
+            println!(
+                "    {}={} ({})",
+                name,
+                prop.choices()
+                    .map(|choices| choices.join("|"))
+                    .unwrap_or_else(|| prop.type_name().into()),
+                details.join(", "),
+            );
+            println!("        {}", property.description);
+        }
+    }
+}
+
 /// Show the lock screen
 ///
 /// If `matches` are given, all parameters will be parsed accordingly.
-fn lock<'a>(config: &'a mut Config) -> Result<'a, ()> {
+fn lock(config: &mut Config) -> Result<()> {
     // Create a program intent
     let mut intent = Intent::from(config)?;
 
     // Create a temporary directory
     let temp = TempDir::new(app::NAME)
-        .expect("Failed to create temporary directory");
+        .map_err(|err| Error::other("Failed to create temporary directory").caused_by(err))?;
+
+    // Pipe the raw image to the lock screen if enabled and supported, scripts embed a file
+    let script = config.get(cmd::ARG_SCRIPT).and_then(|path| path.as_str());
+    let raw = script.is_none() && config.get_bool(cmd::ARG_LOCK_RAW).unwrap_or(false);
+    if raw && !intent.supports_raw() {
+        eprintln!(
+            "{} warning: {} doesn't support raw images, using a temporary image file",
+            app::NAME,
+            intent.backend_name(),
+        );
+    }
+    if raw && intent.supports_raw() {
+        // Render the lock image, and remove the screenshot before locking
+        match render_edit(&temp, config) {
+            Ok(edit) => {
+                let dimensions = edit.dimensions();
+                intent.set_raw_image(edit.to_bgrx(), dimensions);
+            },
+            Err(err) => eprintln!("{} error: {}\nLocking without image", app::NAME, err),
+        }
+        temp.close()
+            .map_err(|err| Error::other("Failed to remove temporary directory").caused_by(err))?;
+
+        return finish(config, &intent);
+    }
 
-    // Create a screenshot
-    let screenshot = screenshot(&temp, config);
+    // Create the lock image
+    let image = render(&temp, &temp.path().join("i3lock-image.png"), config);
 
-    // Configure to use the screenshot as lock image
-    match screenshot {
-        Ok(file) => {
-            intent.push_arg("--image".into());
-            intent.push_arg(file.to_str().unwrap().into());
+    let image = match image {
+        Ok(file) => Some(file),
+        Err(err) => {
+            eprintln!("{} error: {}\nLocking without image", app::NAME, err);
+            None
         },
-        _ => {}
+    };
+
+    // Export a standalone script that embeds the image
+    if let Some(path) = script {
+        return export_script(path, &intent, image.as_ref());
+    }
+
+    // Configure to use the rendered lock image
+    if let Some(file) = image {
+        intent.set_image(file.to_str().unwrap().into());
     }
 
-    // Invoke i3lock, or output it's command
+    finish(config, &intent)
+}
+
+/// Invoke the lock screen for the given `intent`, or output it's command on a dry run.
+fn finish(config: &Config, intent: &Intent) -> Result<()> {
+    // Invoke the lock screen, or output it's command
     if !config.get_bool(cmd::ARG_DRY).unwrap_or(false) {
-        intent.run().unwrap();
+        intent.run()?;
     } else {
-        // TODO: Escape arguments with spaces and other weird characters?
         println!("{}", intent.command());
     }
 
     Ok(())
 }
 
-/// Take a screenshot and save in the given temporary directory.
+/// Export a standalone shell script to the given `path`, that invokes the lock screen with the given
+/// `intent` and the rendered `image` embedded.
+fn export_script(path: &str, intent: &Intent, image: Option<&PathBuf>) -> Result<()> {
+    // Read the image to embed, and build the script
+    let data = match image {
+        Some(file) => Some(fs::read(file)?),
+        None => None,
+    };
+    let script = intent.script(data.as_ref().map(|data| &data[..]));
+
+    // Write the script, and make it executable
+    let path = PathBuf::from(shellexpand::tilde(path).to_string());
+    File::create(&path)
+        .and_then(|mut file| file.write_all(script.as_bytes()))
+        .and_then(|_| fs::set_permissions(&path, fs::Permissions::from_mode(0o755)))
+        .map_err(|err| Error::other(format!("Failed to write lock script to {:?}", path)).caused_by(err))?;
+    println!("Saved lock script to {:?}", path);
+
+    Ok(())
+}
+
+/// Render the lock image, and save it to the given `path` without showing the lock screen.
 ///
-/// Returns a `Path` which references the saved screenshot.
-fn screenshot<'a>(tempdir: &TempDir, config: &Config) -> Result<'a, PathBuf> {
-    // Determine the file path for the screenshot
-    let file = tempdir.path().join("i3lock-image.png");
-
-    // Invoke i3lock
-    println!("Taking screenshot...");
-    let out = Command::new("scrot")
-        .arg("-z")
-        .arg(file.to_str().unwrap())
-        .output()
-        .expect("Failed to invoke i3lock");
-
-    // Process the image
-    let img = img::Img::new(&file);
-    let mut edit = img.edit().unwrap();
-
-    // Get the filters to apply
-    let cfg_filters = config.get_list(cmd::ARG_FILTER, vec![]);
-    let mut filters: Vec<Box<ImgProc>> = Vec::with_capacity(cfg_filters.len());
-
-    // Parse the configuration filters
-    for cfg_filter in cfg_filters {
-        // Get the filter as a string
-        let filter_str: &str = cfg_filter
-            .as_str()
-            .ok_or(Error::new("The filter could not be read as a string"))?;
+/// The image format is determined by the file extension.
+fn render_output(path: &str, config: &Config) -> Result<()> {
+    // Create a temporary directory for the screenshot
+    let temp = TempDir::new(app::NAME)
+        .map_err(|err| Error::other("Failed to create temporary directory").caused_by(err))?;
 
-        // Parse the filter, put it in the list
-        filters.push(ImgProcParser::parse(filter_str)?);
+    // Render the image to the output path
+    let path = PathBuf::from(shellexpand::tilde(path).to_string());
+    render(&temp, &path, config)?;
+    println!("Saved lock image to {:?}", path);
+
+    Ok(())
+}
+
+/// Render the lock image and save it at the given `file` path.
+///
+/// See `render_edit` for how the image is rendered.
+///
+/// Returns a `Path` which references the saved image.
+fn render(tempdir: &TempDir, file: &PathBuf, config: &Config) -> Result<PathBuf> {
+    // Make sure the image can be saved in the requested format before doing any work
+    ImgEdit::format_for(file)?;
+
+    let edit = render_edit(tempdir, config)?;
+
+    println!("Saving edited image...");
+    edit.save(&img::Img::new(file))?;
+
+    Ok(file.clone())
+}
+
+/// Render the lock image, without saving it.
+///
+/// The configured image is loaded, or a screenshot is taken in the given temporary directory
+/// if no image is configured. The configured filters are applied to it.
+fn render_edit(tempdir: &TempDir, config: &Config) -> Result<ImgEdit> {
+    // Parse the configured filters first, so invalid filters fail before capturing anything
+    let filters = parse_filters(config.get_list(cmd::ARG_FILTER, vec![]))?;
+    let monitor_filters = parse_monitor_filters(config)?;
+
+    // Load the configured image, or take a screenshot
+    let image = config.get(cmd::ARG_IMAGE).and_then(|path| path.as_str());
+    let edit = match image {
+        Some(path) => load_image(path, config)?,
+        None => screenshot(&tempdir.path().join("screenshot.png"), config)?,
+    };
+
+    // Process the image, a configured image doesn't follow the monitor layout
+    if image.is_some() {
+        warn_monitor_filters(&monitor_filters, "an image is used instead of a screenshot");
+        return apply_filters(edit, &filters);
     }
+    process(edit, config, &filters, &monitor_filters)
+}
 
-    // Apply the filters
-    for filter in filters {
-        println!("Applying filter...");
-        edit = filter.process_safe(edit).unwrap();
+/// Take a screenshot, and save it at the given `file` path.
+///
+/// The screenshot is returned as editable image.
+fn screenshot(file: &PathBuf, config: &Config) -> Result<ImgEdit> {
+    // Select the screenshot backend, and take the screenshot
+    let backend = BackendSelector::select(
+        config.get(cmd::ARG_SCREENSHOT_BACKEND).and_then(|name| name.as_str())
+    )?;
+    println!("Taking screenshot using {}...", backend.name());
+    backend.capture(file)?;
+
+    img::Img::new(file).edit()
+}
+
+/// Load the image at the given `path`, to use instead of a screenshot.
+///
+/// If the path is a directory, an image is picked from it.
+fn load_image(path: &str, config: &Config) -> Result<ImgEdit> {
+    // Expand the path, and select the image
+    let path = shellexpand::tilde(path).to_string();
+    let pick = config.get(cmd::ARG_IMAGE_PICK)
+        .and_then(|pick| pick.as_str())
+        .unwrap_or(img::img::PICK_RANDOM);
+    let img = img::Img::select(Path::new(&path), pick)?;
+
+    println!("Loading image {:?}...", img.path_buf());
+    img.edit()
+}
+
+/// Apply the given `filters` to the given screenshot `edit`.
+///
+/// If the monitor layout can be detected, the filters are applied to each monitor separately.
+/// A monitor may have it's own list of filters in `monitor_filters`, overriding the global list.
+fn process(
+    mut edit: ImgEdit,
+    config: &Config,
+    filters: &Vec<Box<ImgProc>>,
+    monitor_filters: &HashMap<String, Vec<Box<ImgProc>>>,
+) -> Result<ImgEdit> {
+    // Process the image as a whole if splitting monitors is disabled
+    if !config.get_bool(cmd::ARG_SPLIT_MONITORS).unwrap_or(true) {
+        warn_monitor_filters(monitor_filters, "splitting monitors is disabled");
+        return apply_filters(edit, filters);
     }
 
-    println!("Saving edited image...");
-    if let Err(_) = edit.save(&img) {
-        return Err(Error::new("Failed to save image"));
+    // Process the image as a whole if a filter changes it's size,
+    // because the monitor parts couldn't be put back
+    let changes_size = filters.iter()
+        .chain(monitor_filters.values().flat_map(|filters| filters.iter()))
+        .any(|filter| !filter.keeps_size());
+    if changes_size {
+        println!("A filter changes the image size, processing image as a whole");
+        warn_monitor_filters(monitor_filters, "a filter changes the image size");
+        return apply_filters(edit, filters);
     }
 
-    // Wait for i3lock to complete, handle non-zero status codes
-    if !out.status.success() {
-        println!(
-            "Failed to take screenshot (scrot status code: {})",
-            out.status.code().unwrap()
-        );
+    // Detect the monitors, process the image as a whole if none fit in the image
+    let (width, height) = edit.dimensions();
+    let monitors: Vec<Monitor> = Monitor::detect()
+        .unwrap_or_else(|err| {
+            println!("Failed to detect monitors, processing image as a whole: {}", err);
+            vec![]
+        })
+        .into_iter()
+        .filter(|monitor| monitor.fits(width, height))
+        .collect();
+    if monitors.is_empty() {
+        return apply_filters(edit, filters);
+    }
+
+    // Process each monitor separately
+    for monitor in monitors {
+        println!("Processing monitor {}...", monitor.name);
 
-        if !out.stderr.is_empty() {
-            println!("\nscrot stderr:");
-            println!("==========");
-            println!("{}", String::from_utf8_lossy(&out.stderr));
-            println!("==========");
+        // Process the part of the image this monitor shows, and put it back,
+        // use the filters configured for this monitor if there are any
+        let part = edit.crop(monitor.x, monitor.y, monitor.width, monitor.height);
+        let part = apply_filters(part, monitor_filters.get(&monitor.name).unwrap_or(filters))?;
+        if part.dimensions() != (monitor.width, monitor.height) {
+            return Err(Error::filter("Filters must not change the size of a monitor image"));
         }
+        edit.paste(&part, monitor.x, monitor.y)?;
+    }
+
+    Ok(edit)
+}
+
+/// Warn that the given `monitor_filters` are ignored for the given `reason`, if there are any.
+fn warn_monitor_filters(monitor_filters: &HashMap<String, Vec<Box<ImgProc>>>, reason: &str) {
+    if !monitor_filters.is_empty() {
+        eprintln!(
+            "{} warning: ignoring the filters configured per monitor, because {}",
+            app::NAME,
+            reason,
+        );
+    }
+}
+
+/// Parse the filters configured for specific monitors into image processors, by monitor name.
+fn parse_monitor_filters(config: &Config) -> Result<HashMap<String, Vec<Box<ImgProc>>>> {
+    let mut procs = HashMap::new();
 
-        return Err(Error::new("Failed to take screenshot"));
+    let monitors = match config.get(cmd::ARG_MONITOR).and_then(|monitors| monitors.as_hash()) {
+        Some(monitors) => monitors,
+        None => return Ok(procs),
+    };
+    for (name, monitor) in monitors {
+        // Skip monitors without a filter list
+        let (name, filters) = match (
+            name.as_str(),
+            monitor.property(cmd::ARG_FILTER).and_then(|filters| filters.as_vec()),
+        ) {
+            (Some(name), Some(filters)) => (name, filters),
+            _ => continue,
+        };
+
+        procs.insert(name.to_string(), parse_filters(filters.clone())?);
+    }
+
+    Ok(procs)
+}
+
+/// Parse the given list of `filters` from the configuration into image processors.
+fn parse_filters(filters: Vec<Yaml>) -> Result<Vec<Box<ImgProc>>> {
+    let mut procs: Vec<Box<ImgProc>> = Vec::with_capacity(filters.len());
+
+    for filter in filters {
+        // Get the filter as a string
+        let filter_str: &str = filter
+            .as_str()
+            .ok_or(Error::filter("The filter could not be read as a string"))?;
+
+        // Parse the filter, put it in the list
+        procs.push(ImgProcParser::parse(filter_str)?);
+    }
+
+    Ok(procs)
+}
+
+/// Apply the given list of `filters` to the image `edit` in order.
+fn apply_filters(mut edit: ImgEdit, filters: &Vec<Box<ImgProc>>) -> Result<ImgEdit> {
+    for filter in filters {
+        println!("Applying filter...");
+        edit = filter.process_safe(edit)?;
     }
 
-    Ok(file)
+    Ok(edit)
 }
@@ -0,0 +1,128 @@
+extern crate regex;
+
+use std::process::Command;
+
+use self::regex::Regex;
+
+use err::{Error, Result};
+
+/// A connected monitor, with it's geometry on the X screen.
+#[derive(Clone, Debug)]
+pub struct Monitor {
+    /// The output name, such as `eDP-1` or `HDMI-1`.
+    pub name: String,
+
+    /// The horizontal position of the monitor on the screen in pixels.
+    pub x: u32,
+
+    /// The vertical position of the monitor on the screen in pixels.
+    pub y: u32,
+
+    /// The width of the monitor in pixels.
+    pub width: u32,
+
+    /// The height of the monitor in pixels.
+    pub height: u32,
+}
+
+impl Monitor {
+    /// Detect all connected and active monitors by invoking `xrandr`.
+    ///
+    /// An error is returned if `xrandr` could not be invoked or if it failed.
+    pub fn detect() -> Result<Vec<Monitor>> {
+        let out = Command::new("xrandr")
+            .arg("--query")
+            .output()
+            .map_err(|err| Error::other("Failed to invoke xrandr").caused_by(err))?;
+
+        if !out.status.success() {
+            return Err(Error::other("xrandr exited with a non-zero status code"));
+        }
+
+        Ok(Monitor::parse_xrandr(&String::from_utf8_lossy(&out.stdout)))
+    }
+
+    /// Parse the monitors from the given output of `xrandr --query`.
+    ///
+    /// Outputs that are disconnected or that are connected but disabled are skipped.
+    pub fn parse_xrandr(output: &str) -> Vec<Monitor> {
+        // Match lines such as: eDP-1 connected primary 1920x1080+0+0 (normal left ...) 309mm x 174mm
+        let re = Regex::new(
+            r"^(\S+) connected (?:primary )?(\d+)x(\d+)\+(\d+)\+(\d+)"
+        ).unwrap();
+
+        output.lines()
+            .filter_map(|line| re.captures(line))
+            .map(|captures| Monitor {
+                name: captures[1].into(),
+                width: captures[2].parse().unwrap(),
+                height: captures[3].parse().unwrap(),
+                x: captures[4].parse().unwrap(),
+                y: captures[5].parse().unwrap(),
+            })
+            .collect()
+    }
+
+    /// Check whether this monitor fits inside an image with the given dimensions.
+    pub fn fits(&self, width: u32, height: u32) -> bool {
+        self.x + self.width <= width && self.y + self.height <= height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Monitor;
+
+    /// Output of `xrandr --query` with a primary laptop screen, an external monitor,
+    /// a connected but disabled output and a disconnected output.
+    const XRANDR: &'static str = "\
+Screen 0: minimum 8 x 8, current 3840 x 1080, maximum 32767 x 32767
+eDP-1 connected primary 1920x1080+0+0 (normal left inverted right x axis y axis) 309mm x 174mm
+   1920x1080     60.02*+  59.93
+   1680x1050     59.88
+HDMI-1 connected 1920x1200+1920+0 (normal left inverted right x axis y axis) 518mm x 324mm
+   1920x1200     59.95*+
+DP-1 connected (normal left inverted right x axis y axis)
+   2560x1440     59.95 +
+DP-2 disconnected (normal left inverted right x axis y axis)
+";
+
+    #[test]
+    fn parse_xrandr() {
+        let monitors = Monitor::parse_xrandr(XRANDR);
+        let found: Vec<_> = monitors.iter()
+            .map(|m| (m.name.as_str(), m.x, m.y, m.width, m.height))
+            .collect();
+        assert_eq!(found, vec![
+            ("eDP-1", 0, 0, 1920, 1080),
+            ("HDMI-1", 1920, 0, 1920, 1200),
+        ]);
+    }
+
+    #[test]
+    fn parse_xrandr_primary() {
+        let monitors = Monitor::parse_xrandr("LVDS1 connected primary 1366x768+0+0 (normal) 0mm x 0mm\n");
+        assert_eq!(monitors.len(), 1);
+        assert_eq!(monitors[0].name, "LVDS1");
+        assert_eq!((monitors[0].width, monitors[0].height), (1366, 768));
+    }
+
+    #[test]
+    fn parse_xrandr_skips_inactive() {
+        let output = "\
+DP-1 connected (normal left inverted right x axis y axis)
+DP-2 disconnected (normal left inverted right x axis y axis)
+VGA-1 disconnected 1024x768+0+0 (normal) 0mm x 0mm
+";
+        assert!(Monitor::parse_xrandr(output).is_empty());
+        assert!(Monitor::parse_xrandr("").is_empty());
+    }
+
+    #[test]
+    fn fits() {
+        let monitor = Monitor { name: "HDMI-1".into(), x: 1920, y: 0, width: 1920, height: 1200 };
+        assert!(monitor.fits(3840, 1200));
+        assert!(!monitor.fits(3840, 1080));
+        assert!(!monitor.fits(1920, 1200));
+    }
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+use std::process::Command;
+
+use err::{Error, Result};
+use util;
+
+/// Screenshot backend trait.
+///
+/// A backend invokes an external tool to capture the whole screen into an image file.
+pub trait Backend {
+    /// The name of the backend, as used in the configuration.
+    fn name(&self) -> &'static str;
+
+    /// The binaries that must be available on the `PATH` to use this backend.
+    fn binaries(&self) -> &'static [&'static str];
+
+    /// Build the command that captures the screen into the PNG file at the given `file` path.
+    fn command(&self, file: &Path) -> Command;
+
+    /// Check whether this backend is available, by finding all it's binaries on the `PATH`.
+    fn is_available(&self) -> bool {
+        self.binaries()
+            .iter()
+            .all(|bin| util::find_binary(bin).is_some())
+    }
+
+    /// Capture the screen into the PNG file at the given `file` path.
+    ///
+    /// An error is returned if the tool could not be invoked, or if it failed.
+    fn capture(&self, file: &Path) -> Result<()> {
+        // Invoke the screenshot tool
+        let out = self.command(file)
+            .output()
+            .map_err(|err| Error::screenshot(format!("Failed to invoke screenshot tool {}", self.name())).caused_by(err))?;
+
+        // Handle non-zero status codes
+        if !out.status.success() {
+            println!(
+                "Failed to take screenshot ({} status code: {})",
+                self.name(),
+                out.status.code().map(|code| code.to_string()).unwrap_or("none".into()),
+            );
+
+            if !out.stderr.is_empty() {
+                println!("\n{} stderr:", self.name());
+                println!("==========");
+                println!("{}", String::from_utf8_lossy(&out.stderr));
+                println!("==========");
+            }
+
+            return Err(Error::screenshot("Failed to take screenshot"));
+        }
+
+        // The tool must have created the file
+        if !file.is_file() {
+            return Err(Error::screenshot("Failed to take screenshot, the screenshot tool didn't save an image"));
+        }
+
+        Ok(())
+    }
+}
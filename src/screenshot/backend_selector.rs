@@ -0,0 +1,63 @@
+use std::env;
+
+use super::*;
+
+use err::{Error, Result};
+
+/// Screenshot backend selector.
+///
+/// This creates the screenshot backend by it's name from the configuration,
+/// or detects which backend to use based on the tools that are available.
+pub struct BackendSelector {}
+
+impl BackendSelector {
+    /// Select the backend with the given `name`, or detect an available backend if `None`.
+    ///
+    /// An error is returned if the name is unknown, or if no backend is available.
+    pub fn select(name: Option<&str>) -> Result<Box<Backend>> {
+        match name {
+            Some(name) => BackendSelector::create_backend_by_name(name),
+            None => BackendSelector::detect(),
+        }
+    }
+
+    /// Create a backend instance by the given backend `name`.
+    ///
+    /// An error is returned if the backend name is unknown.
+    pub fn create_backend_by_name(name: &str) -> Result<Box<Backend>> {
+        match name.trim().to_lowercase().as_str() {
+            "grim" => Ok(Box::new(Grim::new())),
+            "import" => Ok(Box::new(Import::new())),
+            "maim" => Ok(Box::new(Maim::new())),
+            "scrot" => Ok(Box::new(Scrot::new())),
+            "xwd" => Ok(Box::new(Xwd::new())),
+            _ => Err(Error::screenshot("Unknown screenshot backend name")),
+        }
+    }
+
+    /// Detect the first available backend, based on the tools found on the `PATH`.
+    ///
+    /// On Wayland `grim` is preferred.
+    ///
+    /// An error is returned if no backend is available.
+    pub fn detect() -> Result<Box<Backend>> {
+        // Build the list of backends in order of preference
+        let mut backends: Vec<Box<Backend>> = vec![
+            Box::new(Scrot::new()),
+            Box::new(Maim::new()),
+            Box::new(Import::new()),
+            Box::new(Xwd::new()),
+        ];
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            backends.insert(0, Box::new(Grim::new()));
+        } else {
+            backends.push(Box::new(Grim::new()));
+        }
+
+        backends.into_iter()
+            .find(|backend| backend.is_available())
+            .ok_or(Error::screenshot(
+                "No screenshot tool found, install scrot, maim, ImageMagick, xwd or grim"
+            ))
+    }
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+use std::process::Command;
+
+use super::Backend;
+
+/// Screenshot backend using `grim`, for Wayland compositors.
+pub struct Grim {}
+
+impl Grim {
+    pub fn new() -> Grim {
+        Grim {}
+    }
+}
+
+impl Backend for Grim {
+    fn name(&self) -> &'static str {
+        "grim"
+    }
+
+    fn binaries(&self) -> &'static [&'static str] {
+        &["grim"]
+    }
+
+    fn command(&self, file: &Path) -> Command {
+        let mut cmd = Command::new("grim");
+        cmd.arg("-t").arg("png").arg(file);
+        cmd
+    }
+}
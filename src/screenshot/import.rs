@@ -0,0 +1,32 @@
+use std::path::Path;
+use std::process::Command;
+
+use super::Backend;
+
+/// Screenshot backend using ImageMagick's `import`.
+pub struct Import {}
+
+impl Import {
+    pub fn new() -> Import {
+        Import {}
+    }
+}
+
+impl Backend for Import {
+    fn name(&self) -> &'static str {
+        "import"
+    }
+
+    fn binaries(&self) -> &'static [&'static str] {
+        &["import"]
+    }
+
+    fn command(&self, file: &Path) -> Command {
+        let mut cmd = Command::new("import");
+        cmd.arg("-silent")
+            .arg("-window")
+            .arg("root")
+            .arg(file);
+        cmd
+    }
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+use std::process::Command;
+
+use super::Backend;
+
+/// Screenshot backend using `maim`.
+pub struct Maim {}
+
+impl Maim {
+    pub fn new() -> Maim {
+        Maim {}
+    }
+}
+
+impl Backend for Maim {
+    fn name(&self) -> &'static str {
+        "maim"
+    }
+
+    fn binaries(&self) -> &'static [&'static str] {
+        &["maim"]
+    }
+
+    fn command(&self, file: &Path) -> Command {
+        let mut cmd = Command::new("maim");
+        cmd.arg("--hidecursor").arg(file);
+        cmd
+    }
+}
@@ -0,0 +1,16 @@
+pub mod backend;
+pub mod backend_selector;
+pub mod grim;
+pub mod import;
+pub mod maim;
+pub mod scrot;
+pub mod xwd;
+
+// Reexport modules
+pub use self::backend::Backend;
+pub use self::backend_selector::BackendSelector;
+pub use self::grim::Grim;
+pub use self::import::Import;
+pub use self::maim::Maim;
+pub use self::scrot::Scrot;
+pub use self::xwd::Xwd;
@@ -0,0 +1,29 @@
+use std::path::Path;
+use std::process::Command;
+
+use super::Backend;
+
+/// Screenshot backend using `scrot`.
+pub struct Scrot {}
+
+impl Scrot {
+    pub fn new() -> Scrot {
+        Scrot {}
+    }
+}
+
+impl Backend for Scrot {
+    fn name(&self) -> &'static str {
+        "scrot"
+    }
+
+    fn binaries(&self) -> &'static [&'static str] {
+        &["scrot"]
+    }
+
+    fn command(&self, file: &Path) -> Command {
+        let mut cmd = Command::new("scrot");
+        cmd.arg("-z").arg(file);
+        cmd
+    }
+}
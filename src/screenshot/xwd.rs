@@ -0,0 +1,36 @@
+use std::path::Path;
+use std::process::Command;
+
+use super::Backend;
+
+/// Screenshot backend using `xwd`.
+///
+/// The XWD image is converted into a PNG image using ImageMagick's `convert`,
+/// because the image crate can't load XWD images.
+pub struct Xwd {}
+
+impl Xwd {
+    pub fn new() -> Xwd {
+        Xwd {}
+    }
+}
+
+impl Backend for Xwd {
+    fn name(&self) -> &'static str {
+        "xwd"
+    }
+
+    fn binaries(&self) -> &'static [&'static str] {
+        &["xwd", "convert"]
+    }
+
+    fn command(&self, file: &Path) -> Command {
+        // Pipe the dump into convert, pass the file as positional shell argument
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("xwd -root -silent | convert xwd:- \"png:$1\"")
+            .arg("sh")
+            .arg(file);
+        cmd
+    }
+}
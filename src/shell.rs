@@ -0,0 +1,96 @@
+/// Characters that don't need quoting in a POSIX shell.
+static SAFE_CHARS: &'static str = "_-+=@%:,./";
+
+/// Quote the given `arg` so a POSIX shell parses it as a single word with the same value.
+///
+/// Arguments that only contain safe characters are returned as is.
+/// Other arguments are wrapped in single quotes, in which no character is special
+/// except for the single quote itself, which is written as `'\''`.
+pub fn quote(arg: &str) -> String {
+    // Keep simple arguments readable
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || SAFE_CHARS.contains(c)) {
+        return arg.into();
+    }
+
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Quote the given `arg` like `quote`, but expand each occurrence of the shell variable `var`
+/// in it, such as `$image`.
+///
+/// The variable is double quoted, so it's value is never split into multiple words.
+pub fn quote_expanding(arg: &str, var: &str) -> String {
+    if !arg.contains(var) {
+        return quote(arg);
+    }
+
+    arg.split(var)
+        .map(|part| if part.is_empty() { String::new() } else { quote(part) })
+        .collect::<Vec<String>>()
+        .join(&format!("\"{}\"", var))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::{quote, quote_expanding};
+
+    /// Evaluate the given shell `words` with `sh`, with `image` set, and get the printed words.
+    fn eval(words: &str) -> Vec<String> {
+        let out = Command::new("sh")
+            .arg("-c")
+            .arg(format!("image='/tmp/lock image.png'; printf '%s\\n' {}", words))
+            .output()
+            .unwrap();
+        String::from_utf8(out.stdout).unwrap().lines().map(|line| line.into()).collect()
+    }
+
+    #[test]
+    fn quote_safe() {
+        assert_eq!(quote("i3lock"), "i3lock");
+        assert_eq!(quote("--color=ff0000"), "--color=ff0000");
+        assert_eq!(quote("/tmp/image_1.png"), "/tmp/image_1.png");
+        assert_eq!(quote("user@host:50%,+1"), "user@host:50%,+1");
+    }
+
+    #[test]
+    fn quote_special() {
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("two words"), "'two words'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote("$HOME"), "'$HOME'");
+        assert_eq!(quote("a\nb"), "'a\nb'");
+        assert_eq!(quote("*"), "'*'");
+    }
+
+    #[test]
+    fn quote_round_trip() {
+        let args = ["", "two  words", "it's", "'", "$HOME", "`id`", "a\\b", "a\"b", "*", "~", "#", ";&|"];
+        let quoted: Vec<String> = args.iter().map(|arg| quote(arg)).collect();
+        assert_eq!(eval(&quoted.join(" ")), args);
+    }
+
+    #[test]
+    fn quote_expanding_var() {
+        assert_eq!(quote_expanding("$image", "$image"), "\"$image\"");
+        assert_eq!(quote_expanding("--image=$image", "$image"), "--image=\"$image\"");
+        assert_eq!(quote_expanding("it's $image", "$image"), "'it'\\''s '\"$image\"");
+        assert_eq!(quote_expanding("$HOME", "$image"), "'$HOME'");
+        assert_eq!(quote_expanding("", "$image"), "''");
+    }
+
+    #[test]
+    fn quote_expanding_round_trip() {
+        let quoted = [
+            quote_expanding("$image", "$image"),
+            quote_expanding("-i=$image;$image's", "$image"),
+            quote_expanding("$HOME", "$image"),
+        ];
+        assert_eq!(eval(&quoted.join(" ")), [
+            "/tmp/lock image.png",
+            "-i=/tmp/lock image.png;/tmp/lock image.png's",
+            "$HOME",
+        ]);
+    }
+}
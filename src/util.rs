@@ -0,0 +1,57 @@
+use std::env;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+/// Find the executable binary with the given `name` on the `PATH`.
+///
+/// The full path to the binary is returned if it was found, `None` otherwise.
+pub fn find_binary(name: &str) -> Option<PathBuf> {
+    let paths = env::var_os("PATH")?;
+
+    env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|path| path.metadata()
+            .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+        )
+}
+
+/// Clamp the given `value` to the range from `min` to `max`.
+///
+/// `f32::clamp` isn't available in the supported Rust versions.
+pub fn clamp<T: PartialOrd>(value: T, min: T, max: T) -> T {
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+/// Compute the Levenshtein edit distance between the strings `a` and `b`.
+///
+/// This is the number of single character insertions, deletions and substitutions
+/// needed to change one string into the other.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+
+    // Keep a single row of the distance matrix
+    let mut row: Vec<usize> = (0..b.len() + 1).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(cur)
+            };
+            prev = cur;
+        }
+    }
+
+    row[b.len()]
+}
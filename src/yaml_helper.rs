@@ -36,7 +36,7 @@ pub trait YamlHelper {
     /// If `node` is an empty string, this object itself it set to the given value.
     ///
     /// If the property isn't found, maps are created automatically to get the proper result structure.
-    fn set_property<'a>(&mut self, node: &str, value: Yaml) -> Result<'a, ()>;
+    fn set_property(&mut self, node: &str, value: Yaml) -> Result<()>;
 
     /// Set a Yaml property at the given `node`.
     ///
@@ -45,7 +45,7 @@ pub trait YamlHelper {
     /// If `node` is an empty string, the `object` itself it set to the given value.
     ///
     /// If the property isn't found, maps are created automatically to get the proper result structure.
-    fn set_property_in<'a>(object: &mut Yaml, node: &str, value: Yaml) -> Result<'a, ()>;
+    fn set_property_in(object: &mut Yaml, node: &str, value: Yaml) -> Result<()>;
 
     /// Check whether the `node` is in this Yaml object.
     ///
@@ -105,11 +105,11 @@ impl YamlHelper for Yaml {
         }
     }
 
-    fn set_property<'a>(&mut self, node: &str, value: Yaml) -> Result<'a, ()> {
+    fn set_property(&mut self, node: &str, value: Yaml) -> Result<()> {
         Yaml::set_property_in(self, node, value)
     }
 
-    fn set_property_in<'a>(object: &mut Yaml, node: &str, value: Yaml) -> Result<'a, ()> {
+    fn set_property_in(object: &mut Yaml, node: &str, value: Yaml) -> Result<()> {
         let node = node.trim();
 
         // Return the object is the node is empty
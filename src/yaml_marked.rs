@@ -0,0 +1,146 @@
+extern crate yaml_rust;
+
+use std::collections::BTreeMap;
+
+use self::yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use self::yaml_rust::scanner::{Marker, ScanError, TScalarStyle};
+use self::yaml_rust::Yaml;
+
+/// A YAML node, along with the position it is defined at in the source.
+///
+/// Unlike `Yaml`, scalars are kept as they are written, so their type can be decided
+/// based on where they are used. This way `000000` can still be used as a color.
+#[derive(Clone, Debug)]
+pub struct Marked {
+    /// The node.
+    pub node: Node,
+
+    /// The position of the node in the source.
+    pub mark: Marker,
+}
+
+/// A YAML node, see `Marked`.
+#[derive(Clone, Debug)]
+pub enum Node {
+    /// A scalar as written in the source, and whether it is plain, not quoted or a block.
+    Scalar(String, bool),
+
+    /// A list of nodes.
+    Seq(Vec<Marked>),
+
+    /// A map of nodes, with the keys in the order they are defined.
+    Map(Vec<(Marked, Marked)>),
+}
+
+impl Marked {
+    /// Load the first YAML document from the given `source`.
+    ///
+    /// `None` is returned if the source doesn't contain a document.
+    pub fn load(source: &str) -> Result<Option<Marked>, ScanError> {
+        let mut loader = Loader::default();
+        Parser::new(source.chars()).load(&mut loader, false)?;
+        Ok(loader.docs.into_iter().next())
+    }
+
+    /// Check whether this node is a null value, such as `~` or a key without a value.
+    pub fn is_null(&self) -> bool {
+        match self.node {
+            Node::Scalar(ref value, true) => Yaml::from_str(value).is_null(),
+            _ => false,
+        }
+    }
+
+    /// Get the value of a scalar as it's written, `None` if this isn't a scalar.
+    pub fn as_scalar(&self) -> Option<&str> {
+        match self.node {
+            Node::Scalar(ref value, _) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Describe the type of this node, for use in messages.
+    pub fn describe(&self) -> &'static str {
+        match self.node {
+            Node::Scalar(..) => "a value",
+            Node::Seq(..) => "a list",
+            Node::Map(..) => "a map",
+        }
+    }
+
+    /// Convert this node into Yaml, resolving the type of plain scalars like YAML does.
+    ///
+    /// Quoted and block scalars are always strings.
+    pub fn to_yaml(&self) -> Yaml {
+        match self.node {
+            Node::Scalar(ref value, true) => Yaml::from_str(value),
+            Node::Scalar(ref value, false) => Yaml::String(value.clone()),
+            Node::Seq(ref items) => Yaml::Array(items.iter().map(|item| item.to_yaml()).collect()),
+            Node::Map(ref entries) => Yaml::Hash(entries.iter()
+                .map(|(key, value)| (key.to_yaml(), value.to_yaml()))
+                .collect()),
+        }
+    }
+
+    /// Build an error with the given `message`, at the position of this node.
+    pub fn error(&self, message: &str) -> ScanError {
+        ScanError::new(self.mark, message)
+    }
+}
+
+/// An event receiver, building the nodes of each loaded document.
+#[derive(Default)]
+struct Loader {
+    /// The loaded documents.
+    docs: Vec<Marked>,
+
+    /// The lists and maps being loaded, with their anchor ID and the key waiting for a value.
+    stack: Vec<(Marked, usize, Option<Marked>)>,
+
+    /// Nodes by their anchor ID, to resolve aliases.
+    anchors: BTreeMap<usize, Marked>,
+}
+
+impl Loader {
+    /// Insert the loaded `marked` node, into the list or map being loaded.
+    fn insert(&mut self, marked: Marked, anchor: usize) {
+        if anchor > 0 {
+            self.anchors.insert(anchor, marked.clone());
+        }
+
+        match self.stack.last_mut() {
+            Some(&mut (Marked { node: Node::Seq(ref mut items), .. }, _, _)) => items.push(marked),
+            Some(&mut (Marked { node: Node::Map(ref mut entries), .. }, _, ref mut key)) => match key.take() {
+                Some(key) => entries.push((key, marked)),
+                None => *key = Some(marked),
+            },
+            Some(_) => {},
+            None => self.docs.push(marked),
+        }
+    }
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, event: &Event, mark: Marker) {
+        match *event {
+            Event::Scalar(ref value, style, anchor, _) => {
+                let node = Node::Scalar(value.clone(), style == TScalarStyle::Plain);
+                self.insert(Marked { node, mark }, anchor);
+            },
+            Event::SequenceStart(anchor) =>
+                self.stack.push((Marked { node: Node::Seq(Vec::new()), mark }, anchor, None)),
+            Event::MappingStart(anchor) =>
+                self.stack.push((Marked { node: Node::Map(Vec::new()), mark }, anchor, None)),
+            Event::SequenceEnd | Event::MappingEnd => if let Some((marked, anchor, _)) = self.stack.pop() {
+                self.insert(marked, anchor);
+            },
+            Event::Alias(anchor) => {
+                // Use the anchored node, but report problems at the alias
+                let node = self.anchors.get(&anchor)
+                    .map(|marked| marked.node.clone())
+                    .unwrap_or_else(|| Node::Scalar("~".into(), true));
+                self.insert(Marked { node, mark }, 0);
+            },
+            _ => {},
+        }
+    }
+}